@@ -4,8 +4,10 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
 
 use tefi_oracle::hub::{
-    AllSourcesResponse, AssetSymbolMapResponse, ConfigResponse, HubExecuteMsg, HubQueryMsg,
-    InstantiateMsg, PriceListResponse, PriceResponse, ProxyWhitelistResponse, SourcesResponse,
+    AllSourcesResponse, AssetSymbolMapResponse, CachedPriceResponse, ConfigResponse,
+    EmaPriceResponse, HubExecuteMsg, HubQueryMsg, InstantiateMsg, JumpLimitResponse,
+    PriceListResponse, PriceResponse, ProxyWhitelistResponse, SourceHealthResponse,
+    SourcesResponse, TwapResponse,
 };
 
 fn main() {
@@ -24,4 +26,9 @@ fn main() {
     export_schema(&schema_for!(SourcesResponse), &out_dir);
     export_schema(&schema_for!(AllSourcesResponse), &out_dir);
     export_schema(&schema_for!(AssetSymbolMapResponse), &out_dir);
+    export_schema(&schema_for!(CachedPriceResponse), &out_dir);
+    export_schema(&schema_for!(TwapResponse), &out_dir);
+    export_schema(&schema_for!(EmaPriceResponse), &out_dir);
+    export_schema(&schema_for!(JumpLimitResponse), &out_dir);
+    export_schema(&schema_for!(SourceHealthResponse), &out_dir);
 }
@@ -6,12 +6,15 @@ use cw2::set_contract_version;
 use tefi_oracle::hub::{HubExecuteMsg, HubQueryMsg, InstantiateMsg};
 
 use crate::handle::{
-    bulk_register_source, insert_asset_symbol_map, register_source, remove_proxy, remove_source,
-    update_max_proxies, update_owner, update_source_priority_list, whitelist_proxy,
+    bulk_register_source, insert_asset_symbol_map, probe_sources, refresh_prices, register_source,
+    remove_proxy, remove_source, update_cache_max_age, update_ema_half_life, update_health_config,
+    update_history_size, update_jump_limit, update_max_proxies, update_owner,
+    update_source_priority_list, whitelist_proxy,
 };
 use crate::query::{
-    query_all_sources, query_asset_symbol_map, query_check_source, query_config, query_price,
-    query_price_list, query_proxy_whitelist, query_sources,
+    query_all_sources, query_asset_symbol_map, query_cached_price, query_check_source,
+    query_config, query_ema_price, query_jump_limit, query_price, query_price_at, query_price_list,
+    query_proxy_whitelist, query_source_health, query_sources, query_twap,
 };
 use crate::state::{Config, ProxyWhitelist, CONFIG, WHITELIST};
 use crate::ContractError;
@@ -33,6 +36,9 @@ pub fn instantiate(
         owner: deps.api.addr_validate(&msg.owner)?,
         base_denom: msg.base_denom,
         max_proxies_per_symbol: msg.max_proxies_per_symbol,
+        cache_max_age: msg.cache_max_age,
+        stale_threshold: msg.stale_threshold,
+        demote_after_failures: msg.demote_after_failures,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -44,7 +50,7 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: HubExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -53,6 +59,13 @@ pub fn execute(
         HubExecuteMsg::UpdateMaxProxies {
             max_proxies_per_symbol,
         } => update_max_proxies(deps, info, max_proxies_per_symbol),
+        HubExecuteMsg::UpdateCacheMaxAge { cache_max_age } => {
+            update_cache_max_age(deps, info, cache_max_age)
+        }
+        HubExecuteMsg::UpdateHealthConfig {
+            stale_threshold,
+            demote_after_failures,
+        } => update_health_config(deps, info, stale_threshold, demote_after_failures),
         HubExecuteMsg::RegisterSource {
             symbol,
             proxy_addr,
@@ -72,6 +85,19 @@ pub fn execute(
         } => whitelist_proxy(deps, info, proxy_addr, provider_name),
         HubExecuteMsg::RemoveProxy { proxy_addr } => remove_proxy(deps, info, proxy_addr),
         HubExecuteMsg::InsertAssetSymbolMap { map } => insert_asset_symbol_map(deps, info, map),
+        HubExecuteMsg::RefreshPrices { symbols } => refresh_prices(deps, env, symbols),
+        HubExecuteMsg::UpdateHistorySize { symbol, size } => {
+            update_history_size(deps, info, symbol, size)
+        }
+        HubExecuteMsg::UpdateEmaHalfLife { symbol, half_life } => {
+            update_ema_half_life(deps, info, symbol, half_life)
+        }
+        HubExecuteMsg::UpdateJumpLimit {
+            symbol,
+            max_change_bps_per_second,
+            clamp,
+        } => update_jump_limit(deps, info, symbol, max_change_bps_per_second, clamp),
+        HubExecuteMsg::ProbeSources { symbols } => probe_sources(deps, env, symbols),
     }
 }
 
@@ -89,10 +115,27 @@ pub fn query(deps: Deps, env: Env, msg: HubQueryMsg) -> Result<Binary, ContractE
         HubQueryMsg::Price {
             asset_token,
             timeframe,
-        } => to_binary(&query_price(deps, env, Some(asset_token), None, timeframe)?),
-        HubQueryMsg::PriceBySymbol { symbol, timeframe } => {
-            to_binary(&query_price(deps, env, None, Some(symbol), timeframe)?)
-        }
+            cache_fallback,
+        } => to_binary(&query_price(
+            deps,
+            env,
+            Some(asset_token),
+            None,
+            timeframe,
+            cache_fallback,
+        )?),
+        HubQueryMsg::PriceBySymbol {
+            symbol,
+            timeframe,
+            cache_fallback,
+        } => to_binary(&query_price(
+            deps,
+            env,
+            None,
+            Some(symbol),
+            timeframe,
+            cache_fallback,
+        )?),
         HubQueryMsg::PriceList { asset_token } => {
             to_binary(&query_price_list(deps, Some(asset_token), None)?)
         }
@@ -108,6 +151,20 @@ pub fn query(deps: Deps, env: Env, msg: HubQueryMsg) -> Result<Binary, ContractE
         HubQueryMsg::CheckSource { proxy_addr, symbol } => {
             to_binary(&query_check_source(deps, proxy_addr, symbol)?)
         }
+        HubQueryMsg::CachedPrice { symbol } => to_binary(&query_cached_price(deps, symbol)?),
+        HubQueryMsg::Twap {
+            symbol,
+            window,
+            timeframe,
+        } => to_binary(&query_twap(deps, env, symbol, window, timeframe)?),
+        HubQueryMsg::PriceAt {
+            symbol,
+            timestamp,
+            timeframe,
+        } => to_binary(&query_price_at(deps, env, symbol, timestamp, timeframe)?),
+        HubQueryMsg::EmaPrice { symbol } => to_binary(&query_ema_price(deps, symbol)?),
+        HubQueryMsg::JumpLimit { symbol } => to_binary(&query_jump_limit(deps, symbol)?),
+        HubQueryMsg::SourceHealth { symbol } => to_binary(&query_source_health(deps, symbol)?),
     };
 
     res.map_err(|err| err.into())
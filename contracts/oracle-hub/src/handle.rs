@@ -1,12 +1,20 @@
 use crate::{
+    math::jump_bounds,
+    query::fetch_price,
     state::{
-        Config, ProxyInfo, ProxyWhitelist, Sources, ASSET_SYMBOL_MAP, CONFIG, SOURCES, WHITELIST,
+        reset_ema, CachedPrice, Config, Ema, JumpLimit, ProxyInfo, ProxyWhitelist, SourceHealth,
+        Sources, ASSET_SYMBOL_MAP, CONFIG, EMAS, JUMP_LIMITS, PRICE_CACHE, SOURCES, SOURCE_HEALTH,
+        WHITELIST,
     },
     ContractError,
 };
-use cosmwasm_std::{Addr, DepsMut, MessageInfo, Response};
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{
+    attr, Addr, Attribute, Decimal, DepsMut, Env, Event, MessageInfo, Order, Response,
+};
 use tefi_oracle::{
-    hub::{DEFAULT_PRIORITY, MAX_WHITELISTED_PROXIES},
+    history::{push_observation, resize_history, Observation},
+    hub::{DEFAULT_PRIORITY, MAX_HISTORY_SIZE, MAX_WHITELISTED_PROXIES},
     querier::query_proxy_symbol_price,
 };
 
@@ -49,6 +57,46 @@ pub fn update_max_proxies(
     Ok(Response::default())
 }
 
+/// Updates the `cache_max_age` parameter
+pub fn update_cache_max_age(
+    deps: DepsMut,
+    info: MessageInfo,
+    cache_max_age: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.cache_max_age = cache_max_age;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+/// Updates the source health parameters
+pub fn update_health_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    stale_threshold: Option<u64>,
+    demote_after_failures: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.stale_threshold = stale_threshold;
+    config.demote_after_failures = demote_after_failures;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
 /// Registers a new price proxy contract for a symbol
 pub fn register_source(
     deps: DepsMut,
@@ -98,6 +146,8 @@ pub fn register_source(
     sources.sort_by_priority();
 
     SOURCES.save(deps.storage, symbol.as_bytes(), &sources)?;
+    // the average of the previous sources is no longer meaningful
+    reset_ema(deps.storage, &symbol)?;
 
     Ok(Response::default())
 }
@@ -148,6 +198,7 @@ pub fn bulk_register_source(
         sources.sort_by_priority();
 
         SOURCES.save(deps.storage, symbol.as_bytes(), &sources)?;
+        reset_ema(deps.storage, &symbol)?;
     }
 
     Ok(Response::default())
@@ -188,6 +239,7 @@ pub fn update_source_priority_list(
     sources.sort_by_priority();
 
     SOURCES.save(deps.storage, symbol.as_bytes(), &sources)?;
+    reset_ema(deps.storage, &symbol)?;
 
     Ok(Response::default())
 }
@@ -212,8 +264,10 @@ pub fn remove_source(
         .map_err(|_| ContractError::SymbolNotRegistered {})?;
 
     sources.remove(&proxy_addr)?;
+    SOURCE_HEALTH.remove(deps.storage, (symbol.as_bytes(), proxy_addr.as_bytes()));
 
     SOURCES.save(deps.storage, symbol.as_bytes(), &sources)?;
+    reset_ema(deps.storage, &symbol)?;
 
     Ok(Response::default())
 }
@@ -278,6 +332,14 @@ pub fn remove_proxy(
 
     WHITELIST.save(deps.storage, &whitelist)?;
 
+    // a proxy whitelisted again later starts with a clean health record
+    let symbols: Vec<Vec<u8>> = SOURCES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect();
+    for symbol in symbols {
+        SOURCE_HEALTH.remove(deps.storage, (&symbol, proxy_addr.as_bytes()));
+    }
+
     Ok(Response::default())
 }
 
@@ -303,6 +365,247 @@ pub fn insert_asset_symbol_map(
     Ok(Response::default())
 }
 
+/// Fetches the current price for each symbol and stores it in the price cache,
+/// recording it in the price history if enabled.
+/// Symbols without an available price keep their previous cached value, as well
+/// as symbols whose price moved beyond their jump limit
+pub fn refresh_prices(
+    deps: DepsMut,
+    env: Env,
+    symbols: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut attributes: Vec<Attribute> = vec![attr("action", "refresh_prices")];
+    let mut events: Vec<Event> = vec![];
+
+    for symbol in symbols {
+        let mut price = match fetch_price(deps.as_ref(), &symbol, 0u64) {
+            Ok(price) => price,
+            Err(ContractError::PriceNotAvailable {}) => {
+                attributes.push(attr("failed", symbol));
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+
+        if let Some(mut limit) = JUMP_LIMITS.may_load(deps.storage, symbol.as_bytes())? {
+            if let Some(previous) = PRICE_CACHE.may_load(deps.storage, symbol.as_bytes())? {
+                let elapsed = env.block.time.seconds().saturating_sub(previous.cached_at);
+                let (lower, upper) = jump_bounds(
+                    previous.rate.into(),
+                    limit.max_change_bps_per_second,
+                    elapsed,
+                );
+
+                let rate: Decimal256 = price.rate.into();
+                if rate < lower || rate > upper {
+                    let bound: Decimal = if rate < lower { lower } else { upper }.into();
+                    let outcome = if limit.clamp { "clamped" } else { "rejected" };
+                    events.push(
+                        Event::new("price_jump")
+                            .add_attribute("symbol", &symbol)
+                            .add_attribute("rate", price.rate.to_string())
+                            .add_attribute("previous_rate", previous.rate.to_string())
+                            .add_attribute("outcome", outcome),
+                    );
+
+                    limit.last_rejected = Some(env.block.time.seconds());
+                    JUMP_LIMITS.save(deps.storage, symbol.as_bytes(), &limit)?;
+
+                    if !limit.clamp {
+                        attributes.push(attr("rejected", symbol));
+                        continue;
+                    }
+                    price.rate = bound;
+                }
+            }
+        }
+
+        PRICE_CACHE.save(
+            deps.storage,
+            symbol.as_bytes(),
+            &CachedPrice {
+                rate: price.rate,
+                last_updated: price.last_updated,
+                cached_at: env.block.time.seconds(),
+            },
+        )?;
+
+        push_observation(
+            deps.storage,
+            &symbol,
+            &Observation {
+                rate: price.rate,
+                timestamp: env.block.time.seconds(),
+            },
+        )?;
+
+        if let Some(mut ema) = EMAS.may_load(deps.storage, symbol.as_bytes())? {
+            ema.update(price.rate, env.block.time.seconds());
+            EMAS.save(deps.storage, symbol.as_bytes(), &ema)?;
+        }
+
+        attributes.push(attr("refreshed", symbol));
+    }
+
+    Ok(Response::new()
+        .add_attributes(attributes)
+        .add_events(events))
+}
+
+/// Updates the number of observations kept in the price history of `symbol`
+pub fn update_history_size(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    size: u32,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if size > MAX_HISTORY_SIZE {
+        return Err(ContractError::InvalidHistorySize {
+            max: MAX_HISTORY_SIZE,
+        });
+    }
+
+    if !SOURCES.has(deps.storage, symbol.as_bytes()) {
+        return Err(ContractError::SymbolNotRegistered {});
+    }
+
+    resize_history(deps.storage, &symbol, size)?;
+
+    Ok(Response::default())
+}
+
+/// Enables, updates or disables the EMA price of `symbol`.
+/// Changing the half-life restarts the average
+pub fn update_ema_half_life(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    half_life: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !SOURCES.has(deps.storage, symbol.as_bytes()) {
+        return Err(ContractError::SymbolNotRegistered {});
+    }
+
+    match half_life {
+        Some(0) => return Err(ContractError::InvalidEmaHalfLife {}),
+        Some(half_life) => EMAS.save(
+            deps.storage,
+            symbol.as_bytes(),
+            &Ema {
+                half_life,
+                rate: None,
+                last_updated: 0u64,
+            },
+        )?,
+        None => EMAS.remove(deps.storage, symbol.as_bytes()),
+    }
+
+    Ok(Response::default())
+}
+
+/// Queries every source of the provided symbols and updates their health counters.
+/// A probe fails if the proxy query fails, and is stale if the price is older than
+/// `stale_threshold`. Both count towards the consecutive failures
+pub fn probe_sources(
+    deps: DepsMut,
+    env: Env,
+    symbols: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    let time_threshold = config
+        .stale_threshold
+        .map(|v| now.saturating_sub(v))
+        .unwrap_or(0u64);
+
+    let mut attributes: Vec<Attribute> = vec![attr("action", "probe_sources")];
+    for symbol in symbols {
+        let sources: Sources = SOURCES
+            .load(deps.storage, symbol.as_bytes())
+            .map_err(|_| ContractError::SymbolNotRegistered {})?;
+
+        for (_prio, proxy_addr) in sources.proxies {
+            let key = (symbol.as_bytes(), proxy_addr.as_bytes());
+            let mut health: SourceHealth = SOURCE_HEALTH
+                .may_load(deps.storage, key)?
+                .unwrap_or_default();
+
+            match query_proxy_symbol_price(&deps.querier, &proxy_addr, symbol.clone()) {
+                Ok(res) if res.last_updated >= time_threshold => {
+                    health.successes += 1;
+                    health.consecutive_failures = 0;
+                }
+                Ok(..) => {
+                    health.stale += 1;
+                    health.consecutive_failures += 1;
+                    attributes.push(attr("stale", format!("{}:{}", symbol, proxy_addr)));
+                }
+                Err(..) => {
+                    health.failures += 1;
+                    health.consecutive_failures += 1;
+                    attributes.push(attr("failed", format!("{}:{}", symbol, proxy_addr)));
+                }
+            }
+            health.last_probed = now;
+
+            SOURCE_HEALTH.save(deps.storage, key, &health)?;
+        }
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Sets or removes the maximum price change per second allowed between refreshes of `symbol`
+pub fn update_jump_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    max_change_bps_per_second: Option<u64>,
+    clamp: bool,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !SOURCES.has(deps.storage, symbol.as_bytes()) {
+        return Err(ContractError::SymbolNotRegistered {});
+    }
+
+    match max_change_bps_per_second {
+        Some(max_change_bps_per_second) => {
+            let last_rejected = JUMP_LIMITS
+                .may_load(deps.storage, symbol.as_bytes())?
+                .and_then(|limit| limit.last_rejected);
+            JUMP_LIMITS.save(
+                deps.storage,
+                symbol.as_bytes(),
+                &JumpLimit {
+                    max_change_bps_per_second,
+                    clamp,
+                    last_rejected,
+                },
+            )?
+        }
+        None => JUMP_LIMITS.remove(deps.storage, symbol.as_bytes()),
+    }
+
+    Ok(Response::default())
+}
+
 // Helper functions
 
 /// check if the provider_name is valid
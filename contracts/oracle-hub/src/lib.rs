@@ -1,5 +1,6 @@
 pub mod contract;
 pub mod handle;
+pub mod math;
 pub mod query;
 pub mod state;
 
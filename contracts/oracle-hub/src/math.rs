@@ -0,0 +1,57 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+
+/// Returns the weight of the previous EMA value after `elapsed` seconds,
+/// computed as `2^(-elapsed / half_life)` so that irregular intervals decay consistently
+pub fn ema_decay(elapsed: u64, half_life: u64) -> Decimal256 {
+    let halvings = elapsed / half_life;
+    // beyond this point the weight is below the decimal precision
+    if halvings >= 60 {
+        return Decimal256::zero();
+    }
+
+    // 2^(-r) = 1 / e^(r * ln(2)) for the fractional part r
+    let remainder = Decimal256::from_ratio(elapsed % half_life, half_life);
+    let ln_2 = Decimal256::from_ratio(693_147_180_559_945_309u64, 1_000_000_000_000_000_000u64);
+    let fractional_decay = Decimal256::one() / exp(remainder * ln_2);
+
+    fractional_decay / Decimal256::from_uint256(Uint256::from(1u64 << halvings))
+}
+
+/// Returns the new EMA value after moving from `ema` towards `rate` with weight `decay` on `ema`
+pub fn ema_update(ema: Decimal256, rate: Decimal256, decay: Decimal256) -> Decimal256 {
+    ema * decay + rate * (Decimal256::one() - decay)
+}
+
+/// Returns the (lower, upper) bounds a price can move to from `previous` after
+/// `elapsed` seconds, given the maximum change in basis points per second
+pub fn jump_bounds(
+    previous: Decimal256,
+    max_change_bps_per_second: u64,
+    elapsed: u64,
+) -> (Decimal256, Decimal256) {
+    let max_change_bps = max_change_bps_per_second.saturating_mul(elapsed);
+    let max_delta = previous * Decimal256::from_ratio(max_change_bps, 10_000u64);
+
+    let lower = if max_delta >= previous {
+        Decimal256::zero()
+    } else {
+        previous - max_delta
+    };
+
+    (lower, previous + max_delta)
+}
+
+/// Computes `e^x` with its Taylor series, only meant for small values of `x`
+fn exp(x: Decimal256) -> Decimal256 {
+    let mut term = Decimal256::one();
+    let mut sum = Decimal256::one();
+    for n in 1..=20u64 {
+        term = term * x / Decimal256::from_uint256(Uint256::from(n));
+        if term.is_zero() {
+            break;
+        }
+        sum += term;
+    }
+
+    sum
+}
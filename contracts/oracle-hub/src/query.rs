@@ -3,17 +3,20 @@ use cw_storage_plus::Bound;
 use tefi_oracle::{
     de::deserialize_key,
     errors::ContractError,
+    history::{load_observations, price_at, time_weighted_average, Observation},
     hub::{
-        AllSourcesResponse, AssetSymbolMapResponse, ConfigResponse, PriceListResponse,
-        PriceQueryResult, PriceResponse, ProxyInfoResponse, ProxyWhitelistResponse,
-        SourcesResponse,
+        AllSourcesResponse, AssetSymbolMapResponse, CachedPriceResponse, ConfigResponse,
+        EmaPriceResponse, JumpLimitResponse, PriceListResponse, PriceQueryResult, PriceResponse,
+        ProxyHealthResponse, ProxyInfoResponse, ProxyWhitelistResponse, SourceHealthResponse,
+        SourcesResponse, TwapResponse,
     },
     proxy::ProxyPriceResponse,
     querier::query_proxy_symbol_price,
 };
 
 use crate::state::{
-    Config, ProxyInfo, ProxyWhitelist, Sources, ASSET_SYMBOL_MAP, CONFIG, SOURCES, WHITELIST,
+    CachedPrice, Config, Ema, JumpLimit, ProxyInfo, ProxyWhitelist, SourceHealth, Sources,
+    ASSET_SYMBOL_MAP, CONFIG, EMAS, JUMP_LIMITS, PRICE_CACHE, SOURCES, SOURCE_HEALTH, WHITELIST,
 };
 
 const DEFAULT_PAGINATION_LIMIT: u32 = 10u32;
@@ -70,6 +73,7 @@ pub fn query_price(
     asset_token: Option<String>,
     symbol: Option<String>,
     timeframe: Option<u64>,
+    cache_fallback: Option<bool>,
 ) -> Result<PriceResponse, ContractError> {
     let symbol = match symbol {
         Some(v) => v,
@@ -87,18 +91,57 @@ pub fn query_price(
         }
     };
 
-    let sources: Sources = SOURCES
-        .load(deps.storage, symbol.as_bytes())
-        .map_err(|_| ContractError::SymbolNotRegistered {})?;
-
     let time_threshold = match timeframe {
         Some(v) => env.block.time.minus_seconds(v).seconds(),
         None => 0u64,
     };
 
-    for (_prio, proxy_addr) in sources.proxies {
+    let err = match fetch_price(deps, &symbol, time_threshold) {
+        Ok(price) => return Ok(price),
+        Err(err) => err,
+    };
+
+    if !cache_fallback.unwrap_or(false) {
+        return Err(err);
+    }
+
+    // fall back to the last known good price if it is recent enough
+    let config: Config = CONFIG.load(deps.storage)?;
+    let cache_max_age = match config.cache_max_age {
+        Some(v) => v,
+        None => return Err(err),
+    };
+
+    match PRICE_CACHE.may_load(deps.storage, symbol.as_bytes())? {
+        Some(cached)
+            if cached.is_fresh(env.block.time.seconds(), cache_max_age)
+                && cached.last_updated >= time_threshold =>
+        {
+            Ok(PriceResponse {
+                rate: cached.rate,
+                last_updated: cached.last_updated,
+            })
+        }
+        _ => Err(err),
+    }
+}
+
+/// Fetches the price from the registered sources of `symbol` in priority order,
+/// returning the first one updated after `time_threshold`.
+/// If demotion is enabled, unhealthy sources are queried last
+pub fn fetch_price(
+    deps: Deps,
+    symbol: &str,
+    time_threshold: u64,
+) -> Result<PriceResponse, ContractError> {
+    let sources: Sources = SOURCES
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::SymbolNotRegistered {})?;
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    for (_prio, proxy_addr) in sources.by_health(deps.storage, config.demote_after_failures)? {
         let proxy_price: ProxyPriceResponse =
-            match query_proxy_symbol_price(&deps.querier, &proxy_addr, symbol.clone()) {
+            match query_proxy_symbol_price(&deps.querier, &proxy_addr, symbol.to_string()) {
                 Ok(res) => res,
                 Err(..) => continue,
             };
@@ -114,6 +157,120 @@ pub fn query_price(
     Err(ContractError::PriceNotAvailable {})
 }
 
+/// Queries the last price stored in the cache for `symbol`
+pub fn query_cached_price(
+    deps: Deps,
+    symbol: String,
+) -> Result<CachedPriceResponse, ContractError> {
+    let cached: CachedPrice = PRICE_CACHE
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::CachedPriceNotFound {})?;
+
+    Ok(cached.as_res(symbol))
+}
+
+/// Queries the time weighted average price of `symbol` over the last `window` seconds,
+/// failing if the latest observation is older than `timeframe`
+pub fn query_twap(
+    deps: Deps,
+    env: Env,
+    symbol: String,
+    window: u64,
+    timeframe: Option<u64>,
+) -> Result<TwapResponse, ContractError> {
+    let observations: Vec<Observation> = load_observations(deps.storage, &symbol)?;
+    let last_updated = match observations.last() {
+        Some(latest) => latest.timestamp,
+        None => return Err(ContractError::HistoryNotAvailable {}),
+    };
+
+    let end = env.block.time.seconds();
+    let start = end
+        .checked_sub(window)
+        .ok_or(ContractError::HistoryNotAvailable {})?;
+    let rate = time_weighted_average(&observations, start, end, timeframe)?;
+
+    Ok(TwapResponse {
+        symbol,
+        rate: rate.into(),
+        window,
+        last_updated,
+    })
+}
+
+/// Queries the price of `symbol` at `timestamp` from the recorded price history,
+/// the latest observation is not extended past `timeframe` seconds
+pub fn query_price_at(
+    deps: Deps,
+    env: Env,
+    symbol: String,
+    timestamp: u64,
+    timeframe: Option<u64>,
+) -> Result<PriceResponse, ContractError> {
+    if timestamp > env.block.time.seconds() {
+        return Err(ContractError::HistoryNotAvailable {});
+    }
+
+    let observations: Vec<Observation> = load_observations(deps.storage, &symbol)?;
+    let rate = price_at(&observations, timestamp, timeframe)?;
+
+    Ok(PriceResponse {
+        rate: rate.into(),
+        last_updated: timestamp,
+    })
+}
+
+/// Queries the EMA price of `symbol`
+pub fn query_ema_price(deps: Deps, symbol: String) -> Result<EmaPriceResponse, ContractError> {
+    let ema: Ema = EMAS
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::EmaNotAvailable {})?;
+
+    ema.as_res(symbol)
+}
+
+/// Queries the health counters of every source registered for `symbol`
+pub fn query_source_health(
+    deps: Deps,
+    symbol: String,
+) -> Result<SourceHealthResponse, ContractError> {
+    let sources: Sources = SOURCES
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::SymbolNotRegistered {})?;
+    let whitelist: ProxyWhitelist = WHITELIST.load(deps.storage)?;
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    let health_list: Vec<ProxyHealthResponse> = sources
+        .proxies
+        .iter()
+        .map(|item| {
+            let health: SourceHealth = SOURCE_HEALTH
+                .may_load(deps.storage, (symbol.as_bytes(), item.1.as_bytes()))?
+                .unwrap_or_default();
+            let proxy_info = whitelist.find_by_addr(&item.1).unwrap_or(ProxyInfo {
+                address: item.1.clone(),
+                provider_name: "No longer whitelisted".to_string(),
+            });
+
+            Ok(health.as_res(item.0, proxy_info, config.demote_after_failures))
+        })
+        .collect::<StdResult<Vec<ProxyHealthResponse>>>()?;
+
+    Ok(SourceHealthResponse {
+        symbol,
+        sources: health_list,
+    })
+}
+
+/// Queries the jump limit configured for `symbol`
+pub fn query_jump_limit(deps: Deps, symbol: String) -> Result<JumpLimitResponse, ContractError> {
+    let limit: JumpLimit = JUMP_LIMITS
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::JumpLimitNotFound {})?;
+
+    Ok(limit.as_res(symbol))
+}
+
 ///  Queries prices from all registered proxies for an `asset_token` or `symbol`
 pub fn query_price_list(
     deps: Deps,
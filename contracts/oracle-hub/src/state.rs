@@ -1,12 +1,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Decimal, StdResult, Storage};
 use cw_storage_plus::{Item, Map};
 
+use crate::math::{ema_decay, ema_update};
 use crate::ContractError;
 use tefi_oracle::hub::{
-    ConfigResponse, ProxyInfoResponse, ProxyWhitelistResponse, SourcesResponse,
+    CachedPriceResponse, ConfigResponse, EmaPriceResponse, JumpLimitResponse, ProxyHealthResponse,
+    ProxyInfoResponse, ProxyWhitelistResponse, SourcesResponse,
 };
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -16,6 +18,14 @@ pub const SOURCES: Map<&[u8], Sources> = Map::new("sources");
 pub const WHITELIST: Item<ProxyWhitelist> = Item::new("whitelist");
 // map of asset cw20 contract addresses to symbol
 pub const ASSET_SYMBOL_MAP: Map<&[u8], String> = Map::new("asset_symbol_map");
+// last known good price for each symbol, stored by `RefreshPrices`
+pub const PRICE_CACHE: Map<&[u8], CachedPrice> = Map::new("price_cache");
+// exponential moving average price for each symbol with EMA enabled
+pub const EMAS: Map<&[u8], Ema> = Map::new("emas");
+// limit of the price change between consecutive refreshes for each symbol
+pub const JUMP_LIMITS: Map<&[u8], JumpLimit> = Map::new("jump_limits");
+// probe counters for each (symbol, proxy_addr) source
+pub const SOURCE_HEALTH: Map<(&[u8], &[u8]), SourceHealth> = Map::new("source_health");
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Config {
@@ -24,6 +34,13 @@ pub struct Config {
     // e.g only proxies compatible with the base_denom should be registered
     pub base_denom: String,
     pub max_proxies_per_symbol: u8,
+    // max age in seconds of a cached price to be used as fallback
+    // if not set, price queries never fall back to the cache
+    pub cache_max_age: Option<u64>,
+    // max price age in seconds for a source probe to succeed
+    pub stale_threshold: Option<u64>,
+    // sources failing this many probes in a row are queried after healthy ones
+    pub demote_after_failures: Option<u32>,
 }
 
 impl Config {
@@ -32,6 +49,9 @@ impl Config {
             owner: self.owner.to_string(),
             base_denom: self.base_denom.to_string(),
             max_proxies_per_symbol: self.max_proxies_per_symbol,
+            cache_max_age: self.cache_max_age,
+            stale_threshold: self.stale_threshold,
+            demote_after_failures: self.demote_after_failures,
         }
     }
 
@@ -132,6 +152,35 @@ impl Sources {
         }
     }
 
+    /// Returns the proxy list with the demoted sources moved after the healthy ones,
+    /// keeping the priority order within each group
+    pub fn by_health(
+        &self,
+        storage: &dyn Storage,
+        demote_after_failures: Option<u32>,
+    ) -> StdResult<Vec<(u8, Addr)>> {
+        let threshold = match demote_after_failures {
+            Some(v) => v,
+            None => return Ok(self.proxies.clone()),
+        };
+
+        let mut healthy: Vec<(u8, Addr)> = vec![];
+        let mut demoted: Vec<(u8, Addr)> = vec![];
+        for item in self.proxies.iter() {
+            let health = SOURCE_HEALTH
+                .may_load(storage, (self.symbol.as_bytes(), item.1.as_bytes()))?
+                .unwrap_or_default();
+            if health.is_demoted(threshold) {
+                demoted.push(item.clone());
+            } else {
+                healthy.push(item.clone());
+            }
+        }
+        healthy.append(&mut demoted);
+
+        Ok(healthy)
+    }
+
     pub fn remove(&mut self, proxy_addr: &Addr) -> Result<(), ContractError> {
         match self.proxies.iter().position(|item| item.1 == *proxy_addr) {
             Some(position) => {
@@ -158,3 +207,127 @@ impl Sources {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CachedPrice {
+    pub rate: Decimal,
+    pub last_updated: u64,
+    pub cached_at: u64,
+}
+
+impl CachedPrice {
+    pub fn as_res(&self, symbol: String) -> CachedPriceResponse {
+        CachedPriceResponse {
+            symbol,
+            rate: self.rate,
+            last_updated: self.last_updated,
+            cached_at: self.cached_at,
+        }
+    }
+
+    /// Checks if the cached price was stored within the last `max_age` seconds
+    pub fn is_fresh(&self, now: u64, max_age: u64) -> bool {
+        self.cached_at.saturating_add(max_age) >= now
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Ema {
+    pub half_life: u64,
+    // not set until the first price is recorded
+    pub rate: Option<Decimal>,
+    pub last_updated: u64,
+}
+
+impl Ema {
+    pub fn as_res(&self, symbol: String) -> Result<EmaPriceResponse, ContractError> {
+        match self.rate {
+            Some(rate) => Ok(EmaPriceResponse {
+                symbol,
+                rate,
+                half_life: self.half_life,
+                last_updated: self.last_updated,
+            }),
+            None => Err(ContractError::EmaNotAvailable {}),
+        }
+    }
+
+    /// Moves the average towards `rate` according to the time elapsed since the last update
+    pub fn update(&mut self, rate: Decimal, timestamp: u64) {
+        let new_rate = match self.rate {
+            Some(ema) => {
+                let elapsed = timestamp.saturating_sub(self.last_updated);
+                let decay = ema_decay(elapsed, self.half_life);
+                ema_update(ema.into(), rate.into(), decay).into()
+            }
+            None => rate,
+        };
+
+        self.rate = Some(new_rate);
+        self.last_updated = timestamp;
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct JumpLimit {
+    pub max_change_bps_per_second: u64,
+    pub clamp: bool,
+    pub last_rejected: Option<u64>,
+}
+
+impl JumpLimit {
+    pub fn as_res(&self, symbol: String) -> JumpLimitResponse {
+        JumpLimitResponse {
+            symbol,
+            max_change_bps_per_second: self.max_change_bps_per_second,
+            clamp: self.clamp,
+            last_rejected: self.last_rejected,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct SourceHealth {
+    pub successes: u64,
+    pub failures: u64,
+    pub stale: u64,
+    pub consecutive_failures: u32,
+    pub last_probed: u64,
+}
+
+impl SourceHealth {
+    pub fn as_res(
+        &self,
+        priority: u8,
+        proxy: ProxyInfo,
+        demote_after_failures: Option<u32>,
+    ) -> ProxyHealthResponse {
+        ProxyHealthResponse {
+            priority,
+            proxy: proxy.as_res(),
+            successes: self.successes,
+            failures: self.failures,
+            stale: self.stale,
+            consecutive_failures: self.consecutive_failures,
+            last_probed: self.last_probed,
+            demoted: demote_after_failures
+                .map(|threshold| self.is_demoted(threshold))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Checks if the source failed at least `threshold` probes in a row
+    pub fn is_demoted(&self, threshold: u32) -> bool {
+        self.consecutive_failures >= threshold
+    }
+}
+
+/// Restarts the EMA of `symbol` from the next recorded price, if enabled
+pub fn reset_ema(storage: &mut dyn Storage, symbol: &str) -> StdResult<()> {
+    if let Some(mut ema) = EMAS.may_load(storage, symbol.as_bytes())? {
+        ema.rate = None;
+        EMAS.save(storage, symbol.as_bytes(), &ema)?;
+    }
+
+    Ok(())
+}
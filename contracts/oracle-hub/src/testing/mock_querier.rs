@@ -25,6 +25,8 @@ pub fn mock_dependencies(
 pub struct WasmMockQuerier {
     base: MockQuerier<Empty>,
     oracle_proxy_price_querier: OracleProxyPriceQuerier,
+    // per proxy price for all symbols, `None` makes the proxy fail
+    proxy_overrides: HashMap<String, Option<Decimal>>,
 }
 
 #[derive(Clone, Default)]
@@ -82,27 +84,30 @@ pub enum ProxyMockQueryMsg {
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match request {
-            QueryRequest::Wasm(WasmQuery::Smart {
-                contract_addr: _,
-                msg,
-            }) => match from_binary(msg).unwrap() {
-                MockQueryMsg::Base(ProxyMockQueryMsg::Price { symbol }) => {
-                    match self.oracle_proxy_price_querier.oracle_price.get(&symbol) {
-                        Some(price) => {
-                            let res = ProxyPriceResponse {
-                                rate: *price,
-                                last_updated: 1000u64,
-                            };
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                match from_binary(msg).unwrap() {
+                    MockQueryMsg::Base(ProxyMockQueryMsg::Price { symbol }) => {
+                        let price = match self.proxy_overrides.get(contract_addr) {
+                            Some(price) => price.as_ref(),
+                            None => self.oracle_proxy_price_querier.oracle_price.get(&symbol),
+                        };
+                        match price {
+                            Some(price) => {
+                                let res = ProxyPriceResponse {
+                                    rate: *price,
+                                    last_updated: 1000u64,
+                                };
 
-                            SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                                SystemResult::Ok(ContractResult::from(to_binary(&res)))
+                            }
+                            None => SystemResult::Err(SystemError::InvalidRequest {
+                                error: "No oracle price exists".to_string(),
+                                request: msg.as_slice().into(),
+                            }),
                         }
-                        None => SystemResult::Err(SystemError::InvalidRequest {
-                            error: "No oracle price exists".to_string(),
-                            request: msg.as_slice().into(),
-                        }),
                     }
                 }
-            },
+            }
             _ => self.base.handle_query(request),
         }
     }
@@ -113,6 +118,7 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             base,
             oracle_proxy_price_querier: OracleProxyPriceQuerier::default(),
+            proxy_overrides: HashMap::new(),
         }
     }
 
@@ -120,4 +126,9 @@ impl WasmMockQuerier {
     pub fn with_proxy_price(&mut self, proxy_prices: &[(&String, &Decimal)]) {
         self.oracle_proxy_price_querier = OracleProxyPriceQuerier::new(proxy_prices);
     }
+
+    // configure the price returned by a specific proxy for any symbol
+    pub fn with_proxy_override(&mut self, proxy_addr: &str, price: Option<Decimal>) {
+        self.proxy_overrides.insert(proxy_addr.to_string(), price);
+    }
 }
@@ -1,11 +1,15 @@
 use crate::contract::{execute, instantiate, query};
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi};
-use cosmwasm_std::{from_binary, Decimal, MemoryStorage, OwnedDeps, Response, StdResult};
+use cosmwasm_std::{
+    attr, from_binary, Decimal, Event, MemoryStorage, OwnedDeps, Response, StdResult,
+};
+use std::str::FromStr;
 use tefi_oracle::errors::ContractError;
 use tefi_oracle::hub::{
-    AllSourcesResponse, AssetSymbolMapResponse, ConfigResponse, HubExecuteMsg as ExecuteMsg,
-    HubQueryMsg as QueryMsg, InstantiateMsg, PriceResponse, ProxyInfoResponse,
-    ProxyWhitelistResponse, SourcesResponse,
+    AllSourcesResponse, AssetSymbolMapResponse, CachedPriceResponse, ConfigResponse,
+    EmaPriceResponse, HubExecuteMsg as ExecuteMsg, HubQueryMsg as QueryMsg, InstantiateMsg,
+    JumpLimitResponse, PriceResponse, ProxyHealthResponse, ProxyInfoResponse,
+    ProxyWhitelistResponse, SourceHealthResponse, SourcesResponse, TwapResponse, MAX_HISTORY_SIZE,
 };
 
 use super::mock_querier::{mock_dependencies, WasmMockQuerier};
@@ -22,6 +26,9 @@ pub fn init(deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>) -> St
         owner: OWNER_ADDR.to_string(),
         base_denom: "uusd".to_string(),
         max_proxies_per_symbol: 10u8,
+        cache_max_age: None,
+        stale_threshold: None,
+        demote_after_failures: None,
     };
     let info = mock_info(OWNER_ADDR, &[]);
     instantiate(deps.as_mut(), mock_env(), info, msg)
@@ -71,6 +78,9 @@ fn proper_initialization() {
             owner: OWNER_ADDR.to_string(),
             base_denom: "uusd".to_string(),
             max_proxies_per_symbol: 10u8,
+            cache_max_age: None,
+            stale_threshold: None,
+            demote_after_failures: None,
         }
     );
 }
@@ -101,6 +111,9 @@ fn test_update_owner() {
             owner: "newowner0000".to_string(),
             base_denom: "uusd".to_string(),
             max_proxies_per_symbol: 10u8,
+            cache_max_age: None,
+            stale_threshold: None,
+            demote_after_failures: None,
         }
     );
 }
@@ -131,6 +144,9 @@ fn test_update_max_proxies() {
             owner: OWNER_ADDR.to_string(),
             base_denom: "uusd".to_string(),
             max_proxies_per_symbol: 20u8, // updated
+            cache_max_age: None,
+            stale_threshold: None,
+            demote_after_failures: None,
         }
     );
 }
@@ -664,6 +680,7 @@ fn test_symbol_map() {
         QueryMsg::Price {
             asset_token: "tsla0000".to_string(),
             timeframe: None,
+            cache_fallback: None,
         },
     )
     .unwrap();
@@ -683,6 +700,7 @@ fn test_symbol_map() {
         QueryMsg::Price {
             asset_token: "random0000".to_string(),
             timeframe: None,
+            cache_fallback: None,
         },
     )
     .unwrap_err();
@@ -873,3 +891,798 @@ fn test_query_pagination() {
         }
     );
 }
+
+#[test]
+fn test_price_cache() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::percent(150))]);
+
+    whitelist_proxy(&mut deps, PROXY_ADDR_1, PROXY_NAME_1).unwrap();
+    register_source(&mut deps, "TSLA", PROXY_ADDR_1, None).unwrap();
+
+    // nothing cached yet
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::CachedPrice {
+            symbol: "TSLA".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CachedPriceNotFound {});
+
+    // anyone can refresh, unregistered symbols fail
+    let info = mock_info("keeper0000", &[]);
+    let msg = ExecuteMsg::RefreshPrices {
+        symbols: vec!["TSLA".to_string(), "AAPL".to_string()],
+    };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::SymbolNotRegistered {});
+
+    let msg = ExecuteMsg::RefreshPrices {
+        symbols: vec!["TSLA".to_string()],
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::CachedPrice {
+            symbol: "TSLA".to_string(),
+        },
+    )
+    .unwrap();
+    let cached: CachedPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        cached,
+        CachedPriceResponse {
+            symbol: "TSLA".to_string(),
+            rate: Decimal::percent(150),
+            last_updated: 1000u64,
+            cached_at: mock_env().block.time.seconds(),
+        }
+    );
+
+    // all sources go down, refresh keeps the previous value
+    deps.querier.with_proxy_price(&[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "refresh_prices"), attr("failed", "TSLA")]
+    );
+
+    let price_msg = QueryMsg::PriceBySymbol {
+        symbol: "TSLA".to_string(),
+        timeframe: None,
+        cache_fallback: Some(true),
+    };
+
+    // fallback is disabled while cache_max_age is not configured
+    let err = query(deps.as_ref(), mock_env(), price_msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::PriceNotAvailable {});
+
+    let msg = ExecuteMsg::UpdateCacheMaxAge {
+        cache_max_age: Some(60u64),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("notowner0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER_ADDR, &[]), msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(60);
+    let res = query(deps.as_ref(), env.clone(), price_msg.clone()).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res,
+        PriceResponse {
+            rate: Decimal::percent(150),
+            last_updated: 1000u64,
+        }
+    );
+
+    // without the fallback mode the query still fails
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::PriceBySymbol {
+            symbol: "TSLA".to_string(),
+            timeframe: None,
+            cache_fallback: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PriceNotAvailable {});
+
+    // cached price is too old
+    env.block.time = env.block.time.plus_seconds(1);
+    let err = query(deps.as_ref(), env, price_msg).unwrap_err();
+    assert_eq!(err, ContractError::PriceNotAvailable {});
+}
+
+#[test]
+fn test_price_history() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::one())]);
+
+    whitelist_proxy(&mut deps, PROXY_ADDR_1, PROXY_NAME_1).unwrap();
+    register_source(&mut deps, "TSLA", PROXY_ADDR_1, None).unwrap();
+
+    let msg = ExecuteMsg::UpdateHistorySize {
+        symbol: "TSLA".to_string(),
+        size: 3u32,
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // size over the maximum
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        owner_info.clone(),
+        ExecuteMsg::UpdateHistorySize {
+            symbol: "TSLA".to_string(),
+            size: MAX_HISTORY_SIZE + 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidHistorySize {
+            max: MAX_HISTORY_SIZE
+        }
+    );
+
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    // record snapshots at t0, t0 + 100 and t0 + 200
+    let start_time = mock_env().block.time.seconds();
+    let refresh_msg = ExecuteMsg::RefreshPrices {
+        symbols: vec!["TSLA".to_string()],
+    };
+    let keeper_info = mock_info("keeper0000", &[]);
+    let mut env = mock_env();
+    for (offset, price) in [(0u64, 100u64), (100, 200), (200, 200)] {
+        deps.querier
+            .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::percent(price))]);
+        env.block.time = mock_env().block.time.plus_seconds(offset);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            keeper_info.clone(),
+            refresh_msg.clone(),
+        )
+        .unwrap();
+    }
+
+    // interpolated between the first two snapshots
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::PriceAt {
+            symbol: "TSLA".to_string(),
+            timestamp: start_time + 50,
+            timeframe: None,
+        },
+    )
+    .unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        price_res,
+        PriceResponse {
+            rate: Decimal::percent(150),
+            last_updated: start_time + 50,
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Twap {
+            symbol: "TSLA".to_string(),
+            window: 200u64,
+            timeframe: None,
+        },
+    )
+    .unwrap();
+    let twap_res: TwapResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        twap_res,
+        TwapResponse {
+            symbol: "TSLA".to_string(),
+            rate: Decimal::percent(175),
+            window: 200u64,
+            last_updated: start_time + 200,
+        }
+    );
+
+    // history does not cover the window
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Twap {
+            symbol: "TSLA".to_string(),
+            window: 201u64,
+            timeframe: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+
+    // the latest snapshot is not extended past the timeframe when refreshes stop
+    let mut stale_env = env.clone();
+    stale_env.block.time = mock_env().block.time.plus_seconds(500);
+    let err = query(
+        deps.as_ref(),
+        stale_env.clone(),
+        QueryMsg::Twap {
+            symbol: "TSLA".to_string(),
+            window: 400u64,
+            timeframe: Some(299u64),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+    let err = query(
+        deps.as_ref(),
+        stale_env.clone(),
+        QueryMsg::PriceAt {
+            symbol: "TSLA".to_string(),
+            timestamp: start_time + 500,
+            timeframe: Some(299u64),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+
+    let res = query(
+        deps.as_ref(),
+        stale_env,
+        QueryMsg::Twap {
+            symbol: "TSLA".to_string(),
+            window: 400u64,
+            timeframe: Some(300u64),
+        },
+    )
+    .unwrap();
+    let twap_res: TwapResponse = from_binary(&res).unwrap();
+    assert_eq!(twap_res.rate, Decimal::percent(200));
+
+    // a new snapshot overwrites the oldest one
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::percent(400))]);
+    env.block.time = mock_env().block.time.plus_seconds(300);
+    execute(deps.as_mut(), env.clone(), keeper_info, refresh_msg).unwrap();
+
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::PriceAt {
+            symbol: "TSLA".to_string(),
+            timestamp: start_time + 50,
+            timeframe: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+
+    // shrinking the buffer keeps the most recent observations
+    let msg = ExecuteMsg::UpdateHistorySize {
+        symbol: "TSLA".to_string(),
+        size: 2u32,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Twap {
+            symbol: "TSLA".to_string(),
+            window: 100u64,
+            timeframe: None,
+        },
+    )
+    .unwrap();
+    let twap_res: TwapResponse = from_binary(&res).unwrap();
+    assert_eq!(twap_res.rate, Decimal::percent(300));
+
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Twap {
+            symbol: "TSLA".to_string(),
+            window: 101u64,
+            timeframe: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+
+    // disabling the history clears it
+    let msg = ExecuteMsg::UpdateHistorySize {
+        symbol: "TSLA".to_string(),
+        size: 0u32,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+
+    let err = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::PriceAt {
+            symbol: "TSLA".to_string(),
+            timestamp: start_time + 300,
+            timeframe: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+}
+
+#[test]
+fn test_ema_price() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::one())]);
+
+    whitelist_proxy(&mut deps, PROXY_ADDR_1, PROXY_NAME_1).unwrap();
+    whitelist_proxy(&mut deps, PROXY_ADDR_2, PROXY_NAME_2).unwrap();
+    register_source(&mut deps, "TSLA", PROXY_ADDR_1, None).unwrap();
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    let msg = ExecuteMsg::UpdateEmaHalfLife {
+        symbol: "TSLA".to_string(),
+        half_life: Some(100u64),
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // invalid half-life
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        owner_info.clone(),
+        ExecuteMsg::UpdateEmaHalfLife {
+            symbol: "TSLA".to_string(),
+            half_life: Some(0u64),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidEmaHalfLife {});
+
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let ema_msg = QueryMsg::EmaPrice {
+        symbol: "TSLA".to_string(),
+    };
+    let err = query(deps.as_ref(), mock_env(), ema_msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::EmaNotAvailable {});
+
+    let refresh_msg = ExecuteMsg::RefreshPrices {
+        symbols: vec!["TSLA".to_string()],
+    };
+    let keeper_info = mock_info("keeper0000", &[]);
+    let mut env = mock_env();
+    let query_ema = |deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>| {
+        let res = query(deps.as_ref(), mock_env(), ema_msg.clone()).unwrap();
+        from_binary::<EmaPriceResponse>(&res).unwrap()
+    };
+
+    // first update initializes the average
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        keeper_info.clone(),
+        refresh_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        query_ema(&deps),
+        EmaPriceResponse {
+            symbol: "TSLA".to_string(),
+            rate: Decimal::one(),
+            half_life: 100u64,
+            last_updated: env.block.time.seconds(),
+        }
+    );
+
+    // after one half-life the average moves half way
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::percent(200))]);
+    env.block.time = env.block.time.plus_seconds(100);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        keeper_info.clone(),
+        refresh_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(query_ema(&deps).rate, Decimal::percent(150));
+
+    // irregular interval of two half-lives
+    env.block.time = env.block.time.plus_seconds(200);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        keeper_info.clone(),
+        refresh_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(query_ema(&deps).rate, Decimal::permille(1875));
+
+    // half a half-life, 2 - 0.125 * 2^(-0.5)
+    env.block.time = env.block.time.plus_seconds(50);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        keeper_info.clone(),
+        refresh_msg.clone(),
+    )
+    .unwrap();
+    let ema_res = query_ema(&deps);
+    assert!(ema_res.rate > Decimal::from_str("1.911611652").unwrap());
+    assert!(ema_res.rate < Decimal::from_str("1.911611653").unwrap());
+    assert_eq!(ema_res.last_updated, env.block.time.seconds());
+
+    // changing the sources resets the average
+    register_source(&mut deps, "TSLA", PROXY_ADDR_2, Some(1u8)).unwrap();
+    let err = query(deps.as_ref(), mock_env(), ema_msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::EmaNotAvailable {});
+
+    env.block.time = env.block.time.plus_seconds(10);
+    execute(deps.as_mut(), env, keeper_info, refresh_msg).unwrap();
+    assert_eq!(query_ema(&deps).rate, Decimal::percent(200));
+
+    // disable the EMA
+    let msg = ExecuteMsg::UpdateEmaHalfLife {
+        symbol: "TSLA".to_string(),
+        half_life: None,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+    let err = query(deps.as_ref(), mock_env(), ema_msg).unwrap_err();
+    assert_eq!(err, ContractError::EmaNotAvailable {});
+}
+
+#[test]
+fn test_jump_limit() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::one())]);
+
+    whitelist_proxy(&mut deps, PROXY_ADDR_1, PROXY_NAME_1).unwrap();
+    register_source(&mut deps, "TSLA", PROXY_ADDR_1, None).unwrap();
+
+    let limit_msg = QueryMsg::JumpLimit {
+        symbol: "TSLA".to_string(),
+    };
+    let err = query(deps.as_ref(), mock_env(), limit_msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::JumpLimitNotFound {});
+
+    // 10 bps per second
+    let msg = ExecuteMsg::UpdateJumpLimit {
+        symbol: "TSLA".to_string(),
+        max_change_bps_per_second: Some(10u64),
+        clamp: false,
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let refresh_msg = ExecuteMsg::RefreshPrices {
+        symbols: vec!["TSLA".to_string()],
+    };
+    let keeper_info = mock_info("keeper0000", &[]);
+    let mut env = mock_env();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        keeper_info.clone(),
+        refresh_msg.clone(),
+    )
+    .unwrap();
+
+    // 20% after 100 seconds is outside the allowed 10% band
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::percent(120))]);
+    env.block.time = env.block.time.plus_seconds(100);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        keeper_info.clone(),
+        refresh_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "refresh_prices"), attr("rejected", "TSLA")]
+    );
+    assert_eq!(
+        res.events,
+        vec![Event::new("price_jump")
+            .add_attribute("symbol", "TSLA")
+            .add_attribute("rate", "1.2")
+            .add_attribute("previous_rate", "1")
+            .add_attribute("outcome", "rejected")]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::CachedPrice {
+            symbol: "TSLA".to_string(),
+        },
+    )
+    .unwrap();
+    let cached: CachedPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(cached.rate, Decimal::one());
+
+    let res = query(deps.as_ref(), env.clone(), limit_msg.clone()).unwrap();
+    let limit_res: JumpLimitResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        limit_res,
+        JumpLimitResponse {
+            symbol: "TSLA".to_string(),
+            max_change_bps_per_second: 10u64,
+            clamp: false,
+            last_rejected: Some(env.block.time.seconds()),
+        }
+    );
+
+    // in clamp mode the price is moved to the edge of the band
+    let msg = ExecuteMsg::UpdateJumpLimit {
+        symbol: "TSLA".to_string(),
+        max_change_bps_per_second: Some(10u64),
+        clamp: true,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        keeper_info.clone(),
+        refresh_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "refresh_prices"), attr("refreshed", "TSLA")]
+    );
+    assert_eq!(res.events.len(), 1);
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::CachedPrice {
+            symbol: "TSLA".to_string(),
+        },
+    )
+    .unwrap();
+    let cached: CachedPriceResponse = from_binary(&res).unwrap();
+    assert_eq!(cached.rate, Decimal::percent(110));
+
+    // movement within the band is accepted
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::percent(115))]);
+    env.block.time = env.block.time.plus_seconds(100);
+    let res = execute(deps.as_mut(), env.clone(), keeper_info, refresh_msg).unwrap();
+    assert!(res.events.is_empty());
+
+    let res = query(deps.as_ref(), env.clone(), limit_msg.clone()).unwrap();
+    let limit_res: JumpLimitResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        limit_res.last_rejected,
+        Some(env.block.time.seconds() - 100)
+    );
+
+    // remove the limit
+    let msg = ExecuteMsg::UpdateJumpLimit {
+        symbol: "TSLA".to_string(),
+        max_change_bps_per_second: None,
+        clamp: false,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+    let err = query(deps.as_ref(), mock_env(), limit_msg).unwrap_err();
+    assert_eq!(err, ContractError::JumpLimitNotFound {});
+}
+
+#[test]
+fn test_source_health() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier
+        .with_proxy_price(&[(&"TSLA".to_string(), &Decimal::one())]);
+
+    whitelist_proxy(&mut deps, PROXY_ADDR_1, PROXY_NAME_1).unwrap();
+    whitelist_proxy(&mut deps, PROXY_ADDR_2, PROXY_NAME_2).unwrap();
+    register_source(&mut deps, "TSLA", PROXY_ADDR_1, Some(1u8)).unwrap();
+    register_source(&mut deps, "TSLA", PROXY_ADDR_2, Some(2u8)).unwrap();
+
+    let msg = ExecuteMsg::UpdateHealthConfig {
+        stale_threshold: None,
+        demote_after_failures: Some(2u32),
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    // proxy 1 fails two probes in a row
+    deps.querier.with_proxy_override(PROXY_ADDR_1, None);
+    let probe_msg = ExecuteMsg::ProbeSources {
+        symbols: vec!["TSLA".to_string()],
+    };
+    let keeper_info = mock_info("keeper0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        keeper_info.clone(),
+        probe_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "probe_sources"),
+            attr("failed", format!("TSLA:{}", PROXY_ADDR_1))
+        ]
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        keeper_info.clone(),
+        probe_msg.clone(),
+    )
+    .unwrap();
+
+    let health_msg = QueryMsg::SourceHealth {
+        symbol: "TSLA".to_string(),
+    };
+    let res = query(deps.as_ref(), mock_env(), health_msg.clone()).unwrap();
+    let health_res: SourceHealthResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        health_res,
+        SourceHealthResponse {
+            symbol: "TSLA".to_string(),
+            sources: vec![
+                ProxyHealthResponse {
+                    priority: 1u8,
+                    proxy: ProxyInfoResponse {
+                        address: PROXY_ADDR_1.to_string(),
+                        provider_name: PROXY_NAME_1.to_string(),
+                    },
+                    successes: 0u64,
+                    failures: 2u64,
+                    stale: 0u64,
+                    consecutive_failures: 2u32,
+                    last_probed: mock_env().block.time.seconds(),
+                    demoted: true,
+                },
+                ProxyHealthResponse {
+                    priority: 2u8,
+                    proxy: ProxyInfoResponse {
+                        address: PROXY_ADDR_2.to_string(),
+                        provider_name: PROXY_NAME_2.to_string(),
+                    },
+                    successes: 2u64,
+                    failures: 0u64,
+                    stale: 0u64,
+                    consecutive_failures: 0u32,
+                    last_probed: mock_env().block.time.seconds(),
+                    demoted: false,
+                }
+            ]
+        }
+    );
+
+    // the demoted proxy is queried after the healthy one, even if it recovered
+    deps.querier
+        .with_proxy_override(PROXY_ADDR_1, Some(Decimal::percent(500)));
+    deps.querier
+        .with_proxy_override(PROXY_ADDR_2, Some(Decimal::percent(200)));
+    let price_msg = QueryMsg::PriceBySymbol {
+        symbol: "TSLA".to_string(),
+        timeframe: None,
+        cache_fallback: None,
+    };
+    let res = query(deps.as_ref(), mock_env(), price_msg.clone()).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::percent(200));
+
+    // a successful probe restores its priority
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        keeper_info.clone(),
+        probe_msg.clone(),
+    )
+    .unwrap();
+    let res = query(deps.as_ref(), mock_env(), price_msg).unwrap();
+    let price_res: PriceResponse = from_binary(&res).unwrap();
+    assert_eq!(price_res.rate, Decimal::percent(500));
+
+    // mocked prices are older than the stale threshold
+    let msg = ExecuteMsg::UpdateHealthConfig {
+        stale_threshold: Some(60u64),
+        demote_after_failures: Some(2u32),
+    };
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        keeper_info.clone(),
+        probe_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "probe_sources"),
+            attr("stale", format!("TSLA:{}", PROXY_ADDR_1)),
+            attr("stale", format!("TSLA:{}", PROXY_ADDR_2))
+        ]
+    );
+
+    let res = query(deps.as_ref(), mock_env(), health_msg.clone()).unwrap();
+    let health_res: SourceHealthResponse = from_binary(&res).unwrap();
+    assert_eq!(health_res.sources[0].successes, 1u64);
+    assert_eq!(health_res.sources[0].stale, 1u64);
+    assert_eq!(health_res.sources[0].consecutive_failures, 1u32);
+    assert!(!health_res.sources[0].demoted);
+
+    // removing the proxy clears its health, it is not demoted when whitelisted again
+    deps.querier.with_proxy_override(PROXY_ADDR_1, None);
+    for _ in 0..2 {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            keeper_info.clone(),
+            probe_msg.clone(),
+        )
+        .unwrap();
+    }
+    let res = query(deps.as_ref(), mock_env(), health_msg.clone()).unwrap();
+    let health_res: SourceHealthResponse = from_binary(&res).unwrap();
+    assert!(health_res.sources[0].demoted);
+
+    let msg = ExecuteMsg::RemoveProxy {
+        proxy_addr: PROXY_ADDR_1.to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER_ADDR, &[]), msg).unwrap();
+    whitelist_proxy(&mut deps, PROXY_ADDR_1, PROXY_NAME_1).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), health_msg).unwrap();
+    let health_res: SourceHealthResponse = from_binary(&res).unwrap();
+    assert_eq!(health_res.sources[0].failures, 0u64);
+    assert_eq!(health_res.sources[0].consecutive_failures, 0u32);
+    assert!(!health_res.sources[0].demoted);
+}
@@ -1,21 +1,38 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, QueryRequest, Response,
-    Uint128, WasmQuery,
+    to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order, QueryRequest,
+    Response, StdResult, Uint128, WasmQuery,
 };
 
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use serde::de::DeserializeOwned;
+use tefi_oracle::de::deserialize_key;
 use tefi_oracle::proxy::{ProxyPriceResponse, ProxyQueryMsg};
 
-use crate::msg::{BandMsg, BandResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG};
+use crate::msg::{
+    BandMsg, BandResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, PricesResponse, QueryMsg,
+    SymbolMapping, SymbolMappingsResponse,
+};
+use crate::state::{BandSymbol, Config, CONFIG, SYMBOL_MAPPINGS};
 use crate::ContractError;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "tefi-oracle-proxy-band";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Band rates are returned with 18 decimals
+const BAND_RATE_PRECISION: u64 = 1_000_000_000_000_000_000u64;
+
+// update time reported by Band for symbols that are always up to date, such as USD
+const BAND_ALWAYS_UPDATED: u64 = u64::MAX;
+
+const DEFAULT_PAGINATION_LIMIT: u32 = 10u32;
+const MAX_PAGINATION_LIMIT: u32 = 30u32;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -29,6 +46,14 @@ pub fn instantiate(
         owner: deps.api.addr_validate(&msg.owner)?,
         source_addr: deps.api.addr_validate(&msg.source_addr)?,
         quote_symbol: msg.quote_symbol,
+        max_base_age: msg.max_base_age,
+        max_quote_age: msg.max_quote_age,
+        fallback_sources: msg
+            .fallback_sources
+            .unwrap_or_default()
+            .iter()
+            .map(|addr| deps.api.addr_validate(addr))
+            .collect::<StdResult<Vec<Addr>>>()?,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -43,19 +68,31 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateConfig { owner, source_addr } => {
-            update_config(deps, info, owner, source_addr)
-        }
+        ExecuteMsg::UpdateConfig {
+            owner,
+            source_addr,
+            fallback_sources,
+        } => update_config(deps, info, owner, source_addr, fallback_sources),
+        ExecuteMsg::UpdateMaxAges {
+            max_base_age,
+            max_quote_age,
+        } => update_max_ages(deps, info, max_base_age, max_quote_age),
+        ExecuteMsg::SetSymbolMappings { mappings } => set_symbol_mappings(deps, info, mappings),
+        ExecuteMsg::RemoveSymbolMapping { symbol } => remove_symbol_mapping(deps, info, symbol),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     let res = match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Prices { symbols } => to_binary(&query_prices(deps, env, symbols)?),
+        QueryMsg::SymbolMappings { start_after, limit } => {
+            to_binary(&query_symbol_mappings(deps, start_after, limit)?)
+        }
         // Implementation of the queries required by proxy contract standard
         QueryMsg::Base(proxy_msg) => match proxy_msg {
-            ProxyQueryMsg::Price { symbol } => to_binary(&query_price(deps, symbol)?),
+            ProxyQueryMsg::Price { symbol } => to_binary(&query_price(deps, env, symbol)?),
         },
     };
 
@@ -66,12 +103,13 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
 /// Execute implementations
 ////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// Updates the `owner` address or `soruce_addr`
+/// Updates the `owner` address, `soruce_addr` or `fallback_sources`
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
     owner: Option<String>,
     source_addr: Option<String>,
+    fallback_sources: Option<Vec<String>>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -89,11 +127,82 @@ pub fn update_config(
         config.source_addr = source_addr;
     }
 
+    if let Some(fallback_sources) = fallback_sources {
+        config.fallback_sources = fallback_sources
+            .iter()
+            .map(|addr| deps.api.addr_validate(addr))
+            .collect::<StdResult<Vec<Addr>>>()?;
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default())
 }
 
+/// Updates the maximum age of the base and quote prices
+pub fn update_max_ages(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_base_age: Option<u64>,
+    max_quote_age: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.max_base_age = max_base_age;
+    config.max_quote_age = max_quote_age;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+/// Registers the Band symbols of hub symbols
+pub fn set_symbol_mappings(
+    deps: DepsMut,
+    info: MessageInfo,
+    mappings: Vec<SymbolMapping>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for mapping in mappings {
+        SYMBOL_MAPPINGS.save(
+            deps.storage,
+            mapping.symbol.as_bytes(),
+            &BandSymbol {
+                base_symbol: mapping.base_symbol,
+                quote_symbol: mapping.quote_symbol,
+            },
+        )?;
+    }
+
+    Ok(Response::default())
+}
+
+/// Removes the Band symbols of a hub symbol
+pub fn remove_symbol_mapping(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    SYMBOL_MAPPINGS.remove(deps.storage, symbol.as_bytes());
+
+    Ok(Response::default())
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////
 /// Query implementations
 ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -105,31 +214,201 @@ pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
     Ok(config.as_res())
 }
 
-/// Queries the price by fetching it from Band source using the previously mapped symbol and converts to the standard format
-pub fn query_price(deps: Deps, symbol: String) -> Result<ProxyPriceResponse, ContractError> {
+/// Queries the symbol mappings
+pub fn query_symbol_mappings(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<SymbolMappingsResponse, ContractError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(MAX_PAGINATION_LIMIT) as usize;
+    let start = start_after.map(|symbol| Bound::exclusive(symbol.as_bytes()));
+
+    let mappings: Vec<SymbolMapping> = SYMBOL_MAPPINGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, band_symbol) = item?;
+            let symbol = deserialize_key::<String>(k)?;
+
+            Ok(band_symbol.as_res(symbol))
+        })
+        .collect::<StdResult<Vec<SymbolMapping>>>()?;
+
+    Ok(SymbolMappingsResponse { mappings })
+}
+
+/// Queries the price by fetching it from Band source using the previously mapped symbol and converts to the standard format.
+/// The older update time of the base and quote legs is reported, and the answer is rejected if a leg is older than its max age
+pub fn query_price(
+    deps: Deps,
+    env: Env,
+    symbol: String,
+) -> Result<ProxyPriceResponse, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
+    let band_symbol: BandSymbol = load_band_symbol(deps, &symbol)?;
+
+    let res: BandResponse = query_band(
+        deps,
+        &config,
+        &BandMsg::GetReferenceData {
+            base_symbol: band_symbol.base_symbol,
+            quote_symbol: band_symbol
+                .quote_symbol
+                .unwrap_or_else(|| config.quote_symbol.clone()),
+        },
+    )?;
+
+    parse_band_response(&res, &config, env.block.time.seconds())
+}
+
+/// Queries the prices of multiple symbols with a single bulk query to Band.
+/// Fails if any of the symbols is not mapped or its answer is rejected
+pub fn query_prices(
+    deps: Deps,
+    env: Env,
+    symbols: Vec<String>,
+) -> Result<PricesResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    let mut base_symbols: Vec<String> = vec![];
+    let mut quote_symbols: Vec<String> = vec![];
+    for symbol in symbols.iter() {
+        let band_symbol: BandSymbol = load_band_symbol(deps, symbol)?;
+
+        base_symbols.push(band_symbol.base_symbol);
+        quote_symbols.push(
+            band_symbol
+                .quote_symbol
+                .unwrap_or_else(|| config.quote_symbol.clone()),
+        );
+    }
+
+    let res: Vec<BandResponse> = query_band(
+        deps,
+        &config,
+        &BandMsg::GetReferenceDataBulk {
+            base_symbols,
+            quote_symbols,
+        },
+    )?;
+
+    if res.len() != symbols.len() {
+        return Err(ContractError::ProxyError {
+            reason: "Band returned an unexpected number of prices".to_string(),
+        });
+    }
+
+    let now: u64 = env.block.time.seconds();
+    let prices: Vec<(String, ProxyPriceResponse)> = symbols
+        .into_iter()
+        .zip(res.iter())
+        .map(|(symbol, band_res)| Ok((symbol, parse_band_response(band_res, &config, now)?)))
+        .collect::<Result<Vec<(String, ProxyPriceResponse)>, ContractError>>()?;
+
+    Ok(PricesResponse { prices })
+}
+
+// Helper functions
+
+/// Loads the Band symbols mapped to `symbol`
+fn load_band_symbol(deps: Deps, symbol: &str) -> Result<BandSymbol, ContractError> {
+    SYMBOL_MAPPINGS
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::ProxyError {
+            reason: format!("Symbol {} is not mapped to a Band symbol", symbol),
+        })
+}
+
+/// Sends the query to the Band contracts in order and returns the first answer
+fn query_band<T: DeserializeOwned>(
+    deps: Deps,
+    config: &Config,
+    msg: &BandMsg,
+) -> Result<T, ContractError> {
+    let msg: Binary = to_binary(msg)?;
+
+    for source in config.sources() {
+        let res: StdResult<T> = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: source.to_string(),
+            msg: msg.clone(),
+        }));
+
+        if let Ok(res) = res {
+            return Ok(res);
+        }
+    }
+
+    Err(ContractError::ProxyError {
+        reason: "None of the Band sources answered".to_string(),
+    })
+}
 
-    let res: BandResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: config.source_addr.to_string(),
-        msg: to_binary(&BandMsg::GetReferenceData {
-            base_symbol: symbol,
-            quote_symbol: config.quote_symbol,
-        })?,
-    }))?;
+/// Checks the age of both legs and converts the Band answer to the standard format
+fn parse_band_response(
+    res: &BandResponse,
+    config: &Config,
+    now: u64,
+) -> Result<ProxyPriceResponse, ContractError> {
+    check_leg_age("base", res.last_updated_base, config.max_base_age, now)?;
+    check_leg_age("quote", res.last_updated_quote, config.max_quote_age, now)?;
 
-    let parsed_rate: Decimal = Decimal::from_ratio(res.rate, Uint128::from(1e18 as u128));
+    let parsed_rate: Decimal = parse_rate(res.rate);
 
     Ok(ProxyPriceResponse {
         rate: parsed_rate,
-        last_updated: res.last_updated_base as u64,
+        last_updated: last_updated(res, now),
     })
 }
 
+/// Converts a Band rate with 18 decimals to a decimal. The integer and fractional parts
+/// are converted separately so that `Decimal::from_ratio` can not overflow
+fn parse_rate(rate: Uint128) -> Decimal {
+    let precision: u128 = BAND_RATE_PRECISION as u128;
+
+    Decimal::from_ratio(rate.u128() / precision, 1u128)
+        + Decimal::from_ratio(rate.u128() % precision, precision)
+}
+
+/// Returns the older update time of the base and quote legs. Band reports `u64::MAX`
+/// for legs that are always up to date (e.g. USD), those are ignored
+fn last_updated(res: &BandResponse, now: u64) -> u64 {
+    [res.last_updated_base, res.last_updated_quote]
+        .iter()
+        .filter(|last_updated| **last_updated != BAND_ALWAYS_UPDATED)
+        .min()
+        .copied()
+        .unwrap_or(now)
+}
+
+/// Checks that a leg updated at `last_updated` is not older than `max_age` seconds
+fn check_leg_age(
+    leg: &str,
+    last_updated: u64,
+    max_age: Option<u64>,
+    now: u64,
+) -> Result<(), ContractError> {
+    if let Some(max_age) = max_age {
+        if last_updated != BAND_ALWAYS_UPDATED && last_updated.saturating_add(max_age) < now {
+            return Err(ContractError::ProxyError {
+                reason: format!(
+                    "Band {} price updated at {} is older than the max age of {} seconds",
+                    leg, last_updated, max_age
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
     use cosmwasm_std::{Decimal, Uint128};
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -141,11 +420,46 @@ mod tests {
             last_updated_quote: u64::MAX,
         };
 
-        let parsed_rate: Decimal = Decimal::from_ratio(band_res.rate, Uint128::from(1e18 as u128));
+        let parsed_rate: Decimal = parse_rate(band_res.rate);
 
         assert_eq!(
             parsed_rate,
             Decimal::from_str("1082.780049999000000000").unwrap()
-        )
+        );
+
+        // the USD quote sentinel is ignored
+        assert_eq!(last_updated(&band_res, 1637951400), 1637951384);
+    }
+
+    #[test]
+    fn test_parse_band_bounds() {
+        assert_eq!(parse_rate(Uint128::zero()), Decimal::zero());
+        assert_eq!(
+            parse_rate(Uint128::new(1u128)),
+            Decimal::from_str("0.000000000000000001").unwrap()
+        );
+        assert_eq!(parse_rate(Uint128::MAX), Decimal::MAX);
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_band_preserves_precision(rate in any::<u128>()) {
+            let precision = BAND_RATE_PRECISION as u128;
+            let expected = Decimal::from_str(
+                &format!("{}.{:018}", rate / precision, rate % precision)
+            ).unwrap();
+
+            // all 18 decimal places are kept over the full range
+            prop_assert_eq!(parse_rate(Uint128::new(rate)), expected);
+        }
+
+        #[test]
+        fn test_parse_band_is_monotonic(a in any::<u128>(), b in any::<u128>()) {
+            let (low, high) = if a <= b { (a, b) } else { (b, a) };
+
+            prop_assert!(
+                parse_rate(Uint128::new(low)) <= parse_rate(Uint128::new(high))
+            );
+        }
     }
 }
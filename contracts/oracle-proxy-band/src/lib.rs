@@ -2,4 +2,7 @@ pub mod contract;
 pub mod msg;
 pub mod state;
 
+#[cfg(test)]
+mod testing;
+
 pub use tefi_oracle::errors::ContractError;
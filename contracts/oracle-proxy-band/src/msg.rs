@@ -1,13 +1,17 @@
 use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tefi_oracle::proxy::ProxyQueryMsg;
+use tefi_oracle::proxy::{ProxyPriceResponse, ProxyQueryMsg};
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: String,
     pub source_addr: String,
     pub quote_symbol: String,
+    pub max_base_age: Option<u64>,
+    pub max_quote_age: Option<u64>,
+    /// Band contracts tried in order when `source_addr` does not answer
+    pub fallback_sources: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -16,7 +20,28 @@ pub enum ExecuteMsg {
     UpdateConfig {
         owner: Option<String>,
         source_addr: Option<String>,
+        fallback_sources: Option<Vec<String>>,
     },
+    /// Sets the maximum age in seconds of the base and quote prices,
+    /// `None` disables the check for that leg
+    UpdateMaxAges {
+        max_base_age: Option<u64>,
+        max_quote_age: Option<u64>,
+    },
+    /// Maps hub symbols to Band symbols, overwrites if already exists
+    SetSymbolMappings { mappings: Vec<SymbolMapping> },
+    /// Removes the mapping of a hub symbol
+    RemoveSymbolMapping { symbol: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SymbolMapping {
+    /// Symbol used by the hub, e.g. mAAPL
+    pub symbol: String,
+    /// Band base symbol, e.g. AAPL
+    pub base_symbol: String,
+    /// Band quote symbol, the `quote_symbol` from config is used if not provided
+    pub quote_symbol: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -24,6 +49,14 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Base(ProxyQueryMsg),
     Config {},
+    /// Prices of multiple symbols, fetched with a single bulk query to Band
+    Prices {
+        symbols: Vec<String>,
+    },
+    SymbolMappings {
+        start_after: Option<String>, // symbol for pagination
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -31,6 +64,19 @@ pub struct ConfigResponse {
     pub owner: String,
     pub source_addr: String,
     pub quote_symbol: String,
+    pub max_base_age: Option<u64>,
+    pub max_quote_age: Option<u64>,
+    pub fallback_sources: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PricesResponse {
+    pub prices: Vec<(String, ProxyPriceResponse)>, // (symbol, price)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SymbolMappingsResponse {
+    pub mappings: Vec<SymbolMapping>,
 }
 
 /// Band Protocol interface
@@ -49,4 +95,9 @@ pub enum BandMsg {
         base_symbol: String,
         quote_symbol: String,
     },
+    /// Response: `Vec<BandResponse>` in the order of the provided symbols
+    GetReferenceDataBulk {
+        base_symbols: Vec<String>,
+        quote_symbols: Vec<String>,
+    },
 }
@@ -2,17 +2,25 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 
-use crate::msg::ConfigResponse;
+use crate::msg::{ConfigResponse, SymbolMapping};
 
 pub const CONFIG: Item<Config> = Item::new("config");
+// Band symbols of each hub symbol
+pub const SYMBOL_MAPPINGS: Map<&[u8], BandSymbol> = Map::new("symbol_mappings");
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Config {
     pub owner: Addr,
     pub source_addr: Addr,
     pub quote_symbol: String,
+    // maximum age in seconds of each leg of the Band answer, not checked if not set
+    pub max_base_age: Option<u64>,
+    pub max_quote_age: Option<u64>,
+    // Band contracts tried in order when `source_addr` does not answer
+    #[serde(default)]
+    pub fallback_sources: Vec<Addr>,
 }
 
 impl Config {
@@ -21,11 +29,42 @@ impl Config {
             owner: self.owner.to_string(),
             source_addr: self.source_addr.to_string(),
             quote_symbol: self.quote_symbol.to_string(),
+            max_base_age: self.max_base_age,
+            max_quote_age: self.max_quote_age,
+            fallback_sources: self
+                .fallback_sources
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect(),
         }
     }
 
+    /// Returns the Band contracts in the order they are queried
+    pub fn sources(&self) -> Vec<&Addr> {
+        let mut sources: Vec<&Addr> = vec![&self.source_addr];
+        sources.extend(self.fallback_sources.iter());
+
+        sources
+    }
+
     /// Checks if the provided `addr` is owner
     pub fn is_owner(&self, addr: &Addr) -> bool {
         self.owner.eq(addr)
     }
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BandSymbol {
+    pub base_symbol: String,
+    pub quote_symbol: Option<String>,
+}
+
+impl BandSymbol {
+    pub fn as_res(&self, symbol: String) -> SymbolMapping {
+        SymbolMapping {
+            symbol,
+            base_symbol: self.base_symbol.to_string(),
+            quote_symbol: self.quote_symbol.clone(),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use std::collections::HashMap;
+
+use crate::msg::{BandMsg, BandResponse};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        api: MockApi::default(),
+        storage: MockStorage::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    // reference data of each Band contract, indexed by (base_symbol, quote_symbol)
+    references: HashMap<String, HashMap<(String, String), BandResponse>>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                let references = match self.references.get(contract_addr) {
+                    Some(references) => references,
+                    None => {
+                        return SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.to_string(),
+                        })
+                    }
+                };
+
+                match from_binary(msg).unwrap() {
+                    BandMsg::GetReferenceData {
+                        base_symbol,
+                        quote_symbol,
+                    } => match references.get(&(base_symbol, quote_symbol)) {
+                        Some(res) => SystemResult::Ok(ContractResult::from(to_binary(res))),
+                        None => SystemResult::Ok(ContractResult::Err("Not found".to_string())),
+                    },
+                    BandMsg::GetReferenceDataBulk {
+                        base_symbols,
+                        quote_symbols,
+                    } => {
+                        let res: Option<Vec<&BandResponse>> = base_symbols
+                            .into_iter()
+                            .zip(quote_symbols)
+                            .map(|pair| references.get(&pair))
+                            .collect();
+                        match res {
+                            Some(res) => SystemResult::Ok(ContractResult::from(to_binary(&res))),
+                            None => SystemResult::Ok(ContractResult::Err("Not found".to_string())),
+                        }
+                    }
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            references: HashMap::new(),
+        }
+    }
+
+    // configure the reference data returned by a Band contract
+    pub fn with_reference(
+        &mut self,
+        source_addr: &str,
+        base_symbol: &str,
+        quote_symbol: &str,
+        res: BandResponse,
+    ) {
+        self.references
+            .entry(source_addr.to_string())
+            .or_default()
+            .insert((base_symbol.to_string(), quote_symbol.to_string()), res);
+    }
+}
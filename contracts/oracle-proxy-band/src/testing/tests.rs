@@ -0,0 +1,324 @@
+use crate::contract::{execute, instantiate, query};
+use crate::msg::{
+    BandResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, PricesResponse, QueryMsg,
+    SymbolMapping, SymbolMappingsResponse,
+};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi};
+use cosmwasm_std::{from_binary, Decimal, MemoryStorage, OwnedDeps, Response, Timestamp, Uint128};
+use std::str::FromStr;
+use tefi_oracle::errors::ContractError;
+use tefi_oracle::proxy::{ProxyPriceResponse, ProxyQueryMsg};
+
+use super::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+const OWNER_ADDR: &str = "owner_0001";
+const BAND_ADDR: &str = "band_0001";
+const FALLBACK_BAND_ADDR: &str = "band_0002";
+
+// helper to successfully init
+pub fn init(
+    deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+) -> Result<Response, ContractError> {
+    let msg = InstantiateMsg {
+        owner: OWNER_ADDR.to_string(),
+        source_addr: BAND_ADDR.to_string(),
+        quote_symbol: "USD".to_string(),
+        max_base_age: None,
+        max_quote_age: None,
+        fallback_sources: None,
+    };
+    let info = mock_info(OWNER_ADDR, &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg)
+}
+
+// helper to build a symbol mapping
+pub fn mapping(symbol: &str, base_symbol: &str, quote_symbol: Option<&str>) -> SymbolMapping {
+    SymbolMapping {
+        symbol: symbol.to_string(),
+        base_symbol: base_symbol.to_string(),
+        quote_symbol: quote_symbol.map(|quote| quote.to_string()),
+    }
+}
+
+// helper to build a Band response
+pub fn reference(rate: &str, last_updated_base: u64, last_updated_quote: u64) -> BandResponse {
+    BandResponse {
+        rate: Uint128::from_str(rate).unwrap(),
+        last_updated_base,
+        last_updated_quote,
+    }
+}
+
+// helper to query the price of a symbol
+pub fn query_price(
+    deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+    symbol: &str,
+) -> Result<ProxyPriceResponse, ContractError> {
+    query_price_at(deps, symbol, mock_env().block.time.seconds())
+}
+
+// helper to query the price of a symbol at the given block time
+pub fn query_price_at(
+    deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+    symbol: &str,
+    now: u64,
+) -> Result<ProxyPriceResponse, ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(now);
+    let msg = QueryMsg::Base(ProxyQueryMsg::Price {
+        symbol: symbol.to_string(),
+    });
+    query(deps.as_ref(), env, msg).map(|res| from_binary(&res).unwrap())
+}
+
+#[test]
+fn test_symbol_mappings() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_reference(
+        BAND_ADDR,
+        "AAPL",
+        "USD",
+        reference("160250000000000000000", 1000, u64::MAX),
+    );
+    deps.querier.with_reference(
+        BAND_ADDR,
+        "LUNA",
+        "UST",
+        reference("85100000000000000000", 1000, 1000),
+    );
+
+    let msg = ExecuteMsg::SetSymbolMappings {
+        mappings: vec![
+            mapping("mAAPL", "AAPL", None),
+            mapping("bLUNA", "LUNA", Some("UST")),
+        ],
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    assert_eq!(
+        query_price(&deps, "mAAPL").unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("160.25").unwrap(),
+            last_updated: 1000u64,
+        }
+    );
+    assert_eq!(
+        query_price(&deps, "bLUNA").unwrap().rate,
+        Decimal::from_str("85.1").unwrap()
+    );
+
+    // unmapped symbols are not forwarded
+    let err = query_price(&deps, "AAPL").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Symbol AAPL is not mapped to a Band symbol".to_string()
+        }
+    );
+
+    let msg = QueryMsg::SymbolMappings {
+        start_after: None,
+        limit: Some(1),
+    };
+    let res: SymbolMappingsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.mappings, vec![mapping("bLUNA", "LUNA", Some("UST"))]);
+
+    let msg = QueryMsg::SymbolMappings {
+        start_after: Some("bLUNA".to_string()),
+        limit: None,
+    };
+    let res: SymbolMappingsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.mappings, vec![mapping("mAAPL", "AAPL", None)]);
+
+    // remove a mapping
+    let msg = ExecuteMsg::RemoveSymbolMapping {
+        symbol: "mAAPL".to_string(),
+    };
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+    assert!(query_price(&deps, "mAAPL").is_err());
+}
+
+#[test]
+fn test_leg_update_times() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_reference(
+        BAND_ADDR,
+        "AAPL",
+        "USD",
+        reference("160250000000000000000", 1000, u64::MAX),
+    );
+    deps.querier.with_reference(
+        BAND_ADDR,
+        "LUNA",
+        "UST",
+        reference("85100000000000000000", 2000, 1500),
+    );
+
+    let msg = ExecuteMsg::SetSymbolMappings {
+        mappings: vec![
+            mapping("mAAPL", "AAPL", None),
+            mapping("bLUNA", "LUNA", Some("UST")),
+        ],
+    };
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    // the older leg is reported, the USD sentinel is ignored
+    assert_eq!(query_price(&deps, "mAAPL").unwrap().last_updated, 1000u64);
+    assert_eq!(query_price(&deps, "bLUNA").unwrap().last_updated, 1500u64);
+
+    let msg = ExecuteMsg::UpdateMaxAges {
+        max_base_age: Some(2000u64),
+        max_quote_age: Some(600u64),
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.max_base_age, Some(2000u64));
+    assert_eq!(config_res.max_quote_age, Some(600u64));
+
+    // the quote leg is never stale for USD
+    assert!(query_price_at(&deps, "mAAPL", 3000).is_ok());
+    let err = query_price_at(&deps, "mAAPL", 3001).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Band base price updated at 1000 is older than the max age of 2000 seconds"
+                .to_string()
+        }
+    );
+
+    assert!(query_price_at(&deps, "bLUNA", 2100).is_ok());
+    let err = query_price_at(&deps, "bLUNA", 2101).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Band quote price updated at 1500 is older than the max age of 600 seconds"
+                .to_string()
+        }
+    );
+}
+
+#[test]
+fn test_bulk_prices_and_fallback_sources() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    // the primary source is down, only the fallback answers
+    deps.querier.with_reference(
+        FALLBACK_BAND_ADDR,
+        "AAPL",
+        "USD",
+        reference("160250000000000000000", 1000, u64::MAX),
+    );
+    deps.querier.with_reference(
+        FALLBACK_BAND_ADDR,
+        "LUNA",
+        "UST",
+        reference("85100000000000000000", 2000, 1500),
+    );
+
+    let msg = ExecuteMsg::SetSymbolMappings {
+        mappings: vec![
+            mapping("mAAPL", "AAPL", None),
+            mapping("bLUNA", "LUNA", Some("UST")),
+        ],
+    };
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let err = query_price(&deps, "mAAPL").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "None of the Band sources answered".to_string()
+        }
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        source_addr: None,
+        fallback_sources: Some(vec![FALLBACK_BAND_ADDR.to_string()]),
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config_res.fallback_sources,
+        vec![FALLBACK_BAND_ADDR.to_string()]
+    );
+
+    assert_eq!(
+        query_price(&deps, "mAAPL").unwrap().rate,
+        Decimal::from_str("160.25").unwrap()
+    );
+
+    let msg = QueryMsg::Prices {
+        symbols: vec!["bLUNA".to_string(), "mAAPL".to_string()],
+    };
+    let res: PricesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        PricesResponse {
+            prices: vec![
+                (
+                    "bLUNA".to_string(),
+                    ProxyPriceResponse {
+                        rate: Decimal::from_str("85.1").unwrap(),
+                        last_updated: 1500u64,
+                    }
+                ),
+                (
+                    "mAAPL".to_string(),
+                    ProxyPriceResponse {
+                        rate: Decimal::from_str("160.25").unwrap(),
+                        last_updated: 1000u64,
+                    }
+                ),
+            ]
+        }
+    );
+
+    // unmapped symbols fail the whole batch
+    let msg = QueryMsg::Prices {
+        symbols: vec!["mAAPL".to_string(), "mTSLA".to_string()],
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Symbol mTSLA is not mapped to a Band symbol".to_string()
+        }
+    );
+}
@@ -1,26 +1,40 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
 use cosmwasm_bignumber::{Decimal256, Uint256};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, QueryRequest, Response,
-    StdResult, WasmQuery,
+    to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper,
+    QueryRequest, Response, StdResult, Storage, Uint128, WasmQuery,
 };
 
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
 use tefi_oracle::de::deserialize_key;
 use tefi_oracle::proxy::{ProxyPriceResponse, ProxyQueryMsg};
 
+use crate::errors::{RoundError, UptimeError};
 use crate::msg::{
-    AggregatorQuery, AggregatorQueryMsg, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
-    RoundDataResponse, SourcesResponse,
+    AggregatorQuery, AggregatorQueryMsg, AggregatorResponse, CompositeSourceMsg, ConfigResponse,
+    ExecuteMsg, InstantiateMsg, Operation, QueryMsg, RoundDataResponse, RoundPriceResponse,
+    RoundTwapResponse, SourceResponse, SourcesResponse,
 };
-use crate::state::{Config, CONFIG, SOURCES};
+use crate::state::{AggregatorInfo, Config, Source, UptimeConfig, AGGREGATORS, CONFIG, SOURCES};
 use crate::ContractError;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "tefi-oracle-proxy-chainlink";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const DEFAULT_PAGINATION_LIMIT: u32 = 10u32;
+const MAX_PAGINATION_LIMIT: u32 = 30u32;
+
+// maximum number of rounds that can be averaged in a single query
+const MAX_TWAP_ROUNDS: u32 = 50;
+
+// maximum number of decimals accepted from an aggregator
+const MAX_DECIMALS: u8 = 36;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -32,6 +46,7 @@ pub fn instantiate(
 
     let config = Config {
         owner: deps.api.addr_validate(&msg.owner)?,
+        uptime: None,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -47,19 +62,38 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::UpdateOwner { owner } => update_owner(deps, info, owner),
-        ExecuteMsg::SetSources { sources } => set_sources(deps, info, sources),
+        ExecuteMsg::SetSources {
+            sources,
+            composite_sources,
+        } => set_sources(deps, info, sources, composite_sources.unwrap_or_default()),
         ExecuteMsg::RemoveSource { symbol } => remove_source(deps, info, symbol),
+        ExecuteMsg::RefreshDecimals { symbols } => refresh_decimals(deps, info, symbols),
+        ExecuteMsg::SetHeartbeats { heartbeats } => set_heartbeats(deps, info, heartbeats),
+        ExecuteMsg::SetUptimeFeed {
+            uptime_feed,
+            grace_period,
+        } => set_uptime_feed(deps, info, uptime_feed, grace_period),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     let res = match msg {
-        QueryMsg::Sources { symbol } => to_binary(&query_sources(deps, symbol)?),
+        QueryMsg::Sources {
+            symbol,
+            start_after,
+            limit,
+        } => to_binary(&query_sources(deps, symbol, start_after, limit)?),
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::PriceAtRound { symbol, round_id } => {
+            to_binary(&query_price_at_round(deps, symbol, round_id)?)
+        }
+        QueryMsg::RoundTwap { symbol, rounds } => {
+            to_binary(&query_round_twap(deps, env, symbol, rounds)?)
+        }
         // Implementation of the queries required by proxy contract standard
         QueryMsg::Base(proxy_msg) => match proxy_msg {
-            ProxyQueryMsg::Price { symbol } => to_binary(&query_price(deps, symbol)?),
+            ProxyQueryMsg::Price { symbol } => to_binary(&query_price(deps, env, symbol)?),
         },
     };
 
@@ -90,11 +124,12 @@ pub fn update_owner(
     Ok(Response::default())
 }
 
-/// Registers Chainlink price sources
+/// Registers Chainlink price sources and caches the decimals of their aggregators
 pub fn set_sources(
-    deps: DepsMut,
+    mut deps: DepsMut,
     info: MessageInfo,
     sources: Vec<(String, String)>,
+    composite_sources: Vec<(String, CompositeSourceMsg)>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
@@ -102,10 +137,29 @@ pub fn set_sources(
         return Err(ContractError::Unauthorized {});
     }
 
-    for (symbol, source) in sources {
-        let source: Addr = deps.api.addr_validate(&source)?;
+    for (symbol, aggregator) in sources {
+        let aggregator: Addr = register_aggregator(deps.branch(), &aggregator)?;
+
+        SOURCES.save(
+            deps.storage,
+            symbol.as_bytes(),
+            &Source::Single { aggregator },
+        )?;
+    }
 
-        SOURCES.save(deps.storage, symbol.as_bytes(), &source)?;
+    for (symbol, composite) in composite_sources {
+        let base: Addr = register_aggregator(deps.branch(), &composite.base)?;
+        let quote: Addr = register_aggregator(deps.branch(), &composite.quote)?;
+
+        SOURCES.save(
+            deps.storage,
+            symbol.as_bytes(),
+            &Source::Composite {
+                base,
+                quote,
+                operation: composite.operation,
+            },
+        )?;
     }
 
     Ok(Response::default())
@@ -128,6 +182,94 @@ pub fn remove_source(
     Ok(Response::default())
 }
 
+/// Updates the cached decimals and description of the aggregators registered for `symbols`
+pub fn refresh_decimals(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbols: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for symbol in symbols {
+        let source: Source = load_source(deps.as_ref(), &symbol)?;
+        for aggregator in source.aggregators() {
+            let decimals: u8 = query_decimals(&deps.querier, aggregator)?;
+            let description: Option<String> = query_description(&deps.querier, aggregator);
+
+            save_aggregator(deps.storage, aggregator, decimals, description)?;
+        }
+    }
+
+    Ok(Response::default())
+}
+
+/// Sets the heartbeat of the aggregators registered for the provided symbols,
+/// both aggregators of a composite source get the same heartbeat
+pub fn set_heartbeats(
+    deps: DepsMut,
+    info: MessageInfo,
+    heartbeats: Vec<(String, Option<u64>)>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (symbol, heartbeat) in heartbeats {
+        let source: Source = load_source(deps.as_ref(), &symbol)?;
+        for address in source.aggregators() {
+            let mut aggregator: AggregatorInfo = load_aggregator(deps.as_ref(), address)?;
+
+            aggregator.heartbeat = heartbeat;
+            AGGREGATORS.save(deps.storage, address.as_bytes(), &aggregator)?;
+        }
+    }
+
+    Ok(Response::default())
+}
+
+/// Sets or disables the uptime feed that gates the price queries
+pub fn set_uptime_feed(
+    deps: DepsMut,
+    info: MessageInfo,
+    uptime_feed: Option<String>,
+    grace_period: u64,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.uptime = match uptime_feed {
+        Some(feed) => {
+            let feed: Addr = deps.api.addr_validate(&feed)?;
+
+            // make sure the feed reports a status before relying on it
+            query_round(
+                &deps.querier,
+                &feed,
+                AggregatorQueryMsg::GetLatestRoundData {},
+            )
+            .map_err(|_| ContractError::ProxyError {
+                reason: format!("Failed to query latest round data from {}", feed),
+            })?;
+
+            Some(UptimeConfig { feed, grace_period })
+        }
+        None => None,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////
 /// Query implementations
 ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -141,63 +283,485 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
 /// Queries the registered Chainlink prices sources
 /// ## Parameters
-/// * `symbol` - (Optional) Asset symbol, if not provided, returns all sources
-pub fn query_sources(deps: Deps, symbol: Option<String>) -> Result<SourcesResponse, ContractError> {
-    let sources: Vec<(String, String)> = match symbol {
+/// * `symbol` - (Optional) Asset symbol, if not provided, returns a page of all sources
+/// * `start_after` - (Optional) Symbol to start after when paginating all sources
+/// * `limit` - (Optional) Maximum number of sources to return when paginating all sources
+pub fn query_sources(
+    deps: Deps,
+    symbol: Option<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<SourcesResponse, ContractError> {
+    let sources: Vec<SourceResponse> = match symbol {
         Some(symbol) => {
-            let source = SOURCES.load(deps.storage, symbol.as_bytes()).map_err(|_| {
-                ContractError::ProxyError {
-                    reason: "Price source not registered".to_string(),
-                }
-            })?;
+            let source: Source = load_source(deps, &symbol)?;
+
+            vec![source_response(deps, symbol, source)?]
+        }
+        None => {
+            let limit = limit
+                .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+                .min(MAX_PAGINATION_LIMIT) as usize;
+            let start = start_after.map(|symbol| Bound::exclusive(symbol.as_bytes()));
+
+            SOURCES
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| {
+                    let (k, v) = item?;
+                    let symbol = deserialize_key::<String>(k)?;
+
+                    source_response(deps, symbol, v)
+                })
+                .collect::<Result<Vec<SourceResponse>, ContractError>>()?
+        }
+    };
+
+    Ok(SourcesResponse { sources })
+}
 
-            vec![(symbol, source.to_string())]
+/// Queries last price feed for the symbol by fetching from Chainlink source and converts to standard format.
+/// Incomplete rounds, zero answers and answers older than the heartbeat of the feed are rejected.
+/// Composite sources combine the rates of both aggregators and report the older update time
+pub fn query_price(
+    deps: Deps,
+    env: Env,
+    symbol: String,
+) -> Result<ProxyPriceResponse, ContractError> {
+    let now: u64 = env.block.time.seconds();
+    assert_system_up(deps, now)?;
+
+    let (rate, last_updated): (Decimal256, u64) = match load_source(deps, &symbol)? {
+        Source::Single { aggregator } => query_latest_rate(deps, &aggregator, now)?,
+        Source::Composite {
+            base,
+            quote,
+            operation,
+        } => {
+            let (base_rate, base_updated) = query_latest_rate(deps, &base, now)?;
+            let (quote_rate, quote_updated) = query_latest_rate(deps, &quote, now)?;
+
+            // an answer with many decimals may still round down to zero
+            if quote_rate.is_zero() {
+                return Err(ContractError::ProxyError {
+                    reason: "Quote aggregator returned a zero rate".to_string(),
+                });
+            }
+
+            // both rates fit in a Decimal, so the result is range checked on conversion
+            let rate: Decimal256 = match operation {
+                Operation::Multiply => base_rate * quote_rate,
+                Operation::Divide => base_rate / quote_rate,
+            };
+
+            (rate, base_updated.min(quote_updated))
         }
-        None => SOURCES
-            .range(deps.storage, None, None, Order::Ascending)
-            .map(|item| {
-                let (k, v) = item.unwrap();
-                let symbol = deserialize_key::<String>(k).unwrap();
+    };
+
+    Ok(ProxyPriceResponse {
+        rate: to_decimal(rate)?,
+        last_updated,
+    })
+}
+
+/// Queries the price of the symbol reported in round `round_id` of its aggregator
+pub fn query_price_at_round(
+    deps: Deps,
+    symbol: String,
+    round_id: u32,
+) -> Result<RoundPriceResponse, ContractError> {
+    let source: Addr = load_single_source(deps, &symbol)?;
+    let aggregator: AggregatorInfo = load_aggregator(deps, &source)?;
+
+    let res: RoundDataResponse = query_round(
+        &deps.querier,
+        &source,
+        AggregatorQueryMsg::GetRoundData { round_id },
+    )
+    .map_err(|_| ContractError::ProxyError {
+        reason: format!("Round {} not available", round_id),
+    })?;
+
+    // the heartbeat only applies to the latest answer
+    let (answer, updated_at) = validate_round(&res, None, 0)?;
+    let parsed_rate: Decimal256 = parse_answer(answer, aggregator.decimals)?;
+
+    Ok(RoundPriceResponse {
+        round_id: res.round_id,
+        rate: to_decimal(parsed_rate)?,
+        last_updated: updated_at,
+    })
+}
+
+/// Queries the average price of the symbol over the latest `rounds` rounds, walking back from
+/// the latest round. Each answer is weighted by the time it was active, until the next round
+/// was updated or until the current block time for the latest round
+pub fn query_round_twap(
+    deps: Deps,
+    env: Env,
+    symbol: String,
+    rounds: u32,
+) -> Result<RoundTwapResponse, ContractError> {
+    if rounds == 0 || rounds > MAX_TWAP_ROUNDS {
+        return Err(ContractError::ProxyError {
+            reason: format!("Number of rounds must be between 1 and {}", MAX_TWAP_ROUNDS),
+        });
+    }
+
+    let now: u64 = env.block.time.seconds();
+    assert_system_up(deps, now)?;
+
+    let source: Addr = load_single_source(deps, &symbol)?;
+    let aggregator: AggregatorInfo = load_aggregator(deps, &source)?;
+
+    let latest: RoundDataResponse = query_round(
+        &deps.querier,
+        &source,
+        AggregatorQueryMsg::GetLatestRoundData {},
+    )?;
+    let (latest_answer, last_updated) = validate_round(&latest, aggregator.heartbeat, now)?;
+
+    let mut weighted_sum = Decimal256::zero();
+    let mut total_duration: u64 = 0;
+    let mut included: u32 = 0;
+
+    let mut round_id: u32 = latest.round_id;
+    let mut answer: Uint128 = latest_answer;
+    let mut updated_at: u64 = last_updated;
+    let mut active_until: u64 = now.max(last_updated);
+    loop {
+        let duration: u64 = active_until - updated_at;
+        weighted_sum += parse_answer(answer, aggregator.decimals)?
+            * Decimal256::from_uint256(Uint256::from(duration));
+        total_duration += duration;
+        included += 1;
+
+        if included == rounds || round_id == 0 {
+            break;
+        }
+
+        // stop walking back when older rounds are not available or their answer was carried over
+        let previous: RoundDataResponse = match query_round(
+            &deps.querier,
+            &source,
+            AggregatorQueryMsg::GetRoundData {
+                round_id: round_id - 1,
+            },
+        ) {
+            Ok(res) => res,
+            Err(_) => break,
+        };
+        let (previous_answer, previous_updated_at) = match validate_round(&previous, None, 0) {
+            Ok(res) => res,
+            Err(RoundError::IncompleteRound { .. }) => break,
+            Err(err) => return Err(err.into()),
+        };
+
+        active_until = updated_at;
+        round_id = previous.round_id;
+        answer = previous_answer;
+        updated_at = previous_updated_at.min(active_until);
+    }
 
-                (symbol, v.to_string())
+    let rate: Decimal256 = if total_duration == 0 {
+        parse_answer(latest_answer, aggregator.decimals)?
+    } else {
+        weighted_sum / Decimal256::from_uint256(Uint256::from(total_duration))
+    };
+
+    Ok(RoundTwapResponse {
+        rate: to_decimal(rate)?,
+        rounds: included,
+        start: updated_at,
+        last_updated,
+    })
+}
+
+// Helper functions
+
+/// Loads the source registered for `symbol`
+fn load_source(deps: Deps, symbol: &str) -> Result<Source, ContractError> {
+    SOURCES
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::ProxyError {
+            reason: "Price source not registered".to_string(),
+        })
+}
+
+/// Loads the aggregator address registered for `symbol`, round ids are specific
+/// to an aggregator so composite sources are rejected
+fn load_single_source(deps: Deps, symbol: &str) -> Result<Addr, ContractError> {
+    match load_source(deps, symbol)? {
+        Source::Single { aggregator } => Ok(aggregator),
+        Source::Composite { .. } => Err(ContractError::ProxyError {
+            reason: "Round queries are not supported for composite sources".to_string(),
+        }),
+    }
+}
+
+/// Loads the cached information of the aggregator at `source`. Aggregators registered
+/// before the decimals were cached are queried directly until their decimals are refreshed
+fn load_aggregator(deps: Deps, source: &Addr) -> Result<AggregatorInfo, ContractError> {
+    if let Some(aggregator) = AGGREGATORS.may_load(deps.storage, source.as_bytes())? {
+        return Ok(aggregator);
+    }
+
+    Ok(AggregatorInfo {
+        decimals: query_decimals(&deps.querier, source)?,
+        heartbeat: None,
+        description: query_description(&deps.querier, source),
+    })
+}
+
+fn source_response(
+    deps: Deps,
+    symbol: String,
+    source: Source,
+) -> Result<SourceResponse, ContractError> {
+    let aggregators: Vec<AggregatorResponse> = source
+        .aggregators()
+        .into_iter()
+        .map(|address| {
+            let aggregator: AggregatorInfo = load_aggregator(deps, address)?;
+
+            Ok(AggregatorResponse {
+                address: address.to_string(),
+                decimals: aggregator.decimals,
+                heartbeat: aggregator.heartbeat,
+                description: aggregator.description,
             })
-            .collect(),
+        })
+        .collect::<Result<Vec<AggregatorResponse>, ContractError>>()?;
+
+    let operation: Option<Operation> = match source {
+        Source::Single { .. } => None,
+        Source::Composite { operation, .. } => Some(operation),
     };
 
-    Ok(SourcesResponse { sources })
+    Ok(SourceResponse {
+        symbol,
+        aggregators,
+        operation,
+    })
 }
 
-/// Queries last price feed for the symbol by fetching from Chainlink source and converts to standard format
-pub fn query_price(deps: Deps, symbol: String) -> Result<ProxyPriceResponse, ContractError> {
-    let source: Addr =
-        SOURCES
-            .load(deps.storage, symbol.as_bytes())
-            .map_err(|_| ContractError::ProxyError {
-                reason: "Price source not registered".to_string(),
-            })?;
+/// Validates the aggregator address, checks that it returns a valid latest round
+/// and caches its decimals and description
+fn register_aggregator(deps: DepsMut, aggregator: &str) -> Result<Addr, ContractError> {
+    let aggregator: Addr = deps.api.addr_validate(aggregator)?;
+
+    let res: RoundDataResponse = query_round(
+        &deps.querier,
+        &aggregator,
+        AggregatorQueryMsg::GetLatestRoundData {},
+    )
+    .map_err(|_| ContractError::ProxyError {
+        reason: format!("Failed to query latest round data from {}", aggregator),
+    })?;
+    validate_round(&res, None, 0)?;
 
-    let res: RoundDataResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+    let decimals: u8 = query_decimals(&deps.querier, &aggregator)?;
+    let description: Option<String> = query_description(&deps.querier, &aggregator);
+
+    save_aggregator(deps.storage, &aggregator, decimals, description)?;
+
+    Ok(aggregator)
+}
+
+/// Queries the latest answer of the aggregator at `source` and returns the validated rate
+/// with its update time
+fn query_latest_rate(
+    deps: Deps,
+    source: &Addr,
+    now: u64,
+) -> Result<(Decimal256, u64), ContractError> {
+    let aggregator: AggregatorInfo = load_aggregator(deps, source)?;
+
+    let res: RoundDataResponse = query_round(
+        &deps.querier,
+        source,
+        AggregatorQueryMsg::GetLatestRoundData {},
+    )?;
+
+    let (answer, updated_at) = validate_round(&res, aggregator.heartbeat, now)?;
+
+    Ok((parse_answer(answer, aggregator.decimals)?, updated_at))
+}
+
+/// Queries round data from the aggregator at `source`
+fn query_round(
+    querier: &QuerierWrapper,
+    source: &Addr,
+    aggregator_query: AggregatorQueryMsg,
+) -> StdResult<RoundDataResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: source.to_string(),
-        msg: to_binary(&AggregatorQuery {
-            aggregator_query: AggregatorQueryMsg::GetLatestRoundData {},
-        })?,
-    }))?;
+        msg: to_binary(&AggregatorQuery { aggregator_query })?,
+    }))
+}
 
-    if res.answer.is_none() || res.updated_at.is_none() {
+/// Queries the number of decimals of the answers returned by the aggregator,
+/// rejecting aggregators that report more than [`MAX_DECIMALS`]
+fn query_decimals(querier: &QuerierWrapper, source: &Addr) -> Result<u8, ContractError> {
+    let decimals: u8 = querier
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: source.to_string(),
+            msg: to_binary(&AggregatorQuery {
+                aggregator_query: AggregatorQueryMsg::GetDecimals {},
+            })?,
+        }))
+        .map_err(|_| ContractError::ProxyError {
+            reason: format!("Failed to query decimals from {}", source),
+        })?;
+
+    if decimals > MAX_DECIMALS {
         return Err(ContractError::ProxyError {
-            reason: "Source did not return answer".to_string(),
+            reason: format!(
+                "Aggregator {} reports {} decimals, at most {} are supported",
+                source, decimals, MAX_DECIMALS
+            ),
         });
     }
 
-    let parsed_rate: Decimal256 = Decimal256::from_ratio(
-        Uint256::from(res.answer.unwrap()),
-        Uint256::from(1e8 as u128),
-    );
+    Ok(decimals)
+}
 
-    Ok(ProxyPriceResponse {
-        rate: parsed_rate.into(),
-        last_updated: res.updated_at.unwrap(),
-    })
+/// Queries the description of the aggregator, `None` if the aggregator does not support it
+fn query_description(querier: &QuerierWrapper, source: &Addr) -> Option<String> {
+    querier
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: source.to_string(),
+            msg: to_binary(&AggregatorQuery {
+                aggregator_query: AggregatorQueryMsg::GetDescription {},
+            })
+            .ok()?,
+        }))
+        .ok()
+}
+
+/// Updates the cached information of the aggregator at `source`, keeping its heartbeat if already registered
+fn save_aggregator(
+    storage: &mut dyn Storage,
+    source: &Addr,
+    decimals: u8,
+    description: Option<String>,
+) -> StdResult<()> {
+    let heartbeat: Option<u64> = AGGREGATORS
+        .may_load(storage, source.as_bytes())?
+        .and_then(|aggregator| aggregator.heartbeat);
+
+    AGGREGATORS.save(
+        storage,
+        source.as_bytes(),
+        &AggregatorInfo {
+            decimals,
+            heartbeat,
+            description,
+        },
+    )
+}
+
+/// Checks that the uptime feed, if configured, reports that the system is up
+/// and recovered more than the grace period before `now`
+fn assert_system_up(deps: Deps, now: u64) -> Result<(), ContractError> {
+    let uptime: UptimeConfig = match CONFIG.load(deps.storage)?.uptime {
+        Some(uptime) => uptime,
+        None => return Ok(()),
+    };
+
+    let res: RoundDataResponse = query_round(
+        &deps.querier,
+        &uptime.feed,
+        AggregatorQueryMsg::GetLatestRoundData {},
+    )
+    .map_err(|_| UptimeError::StatusNotAvailable {})?;
+
+    let (status, recovered_at) = match (res.answer, res.started_at) {
+        (Some(status), Some(started_at)) => (status, started_at),
+        _ => return Err(UptimeError::StatusNotAvailable {}.into()),
+    };
+
+    if !status.is_zero() {
+        return Err(UptimeError::SystemDown {}.into());
+    }
+
+    let grace_period_end: u64 = recovered_at.saturating_add(uptime.grace_period);
+    if now < grace_period_end {
+        return Err(UptimeError::GracePeriodNotOver {
+            recovered_at,
+            grace_period_end,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Checks that a round is complete, has a non-zero answer and, if a heartbeat
+/// is provided, was updated within `heartbeat` seconds of `now`.
+/// Returns the answer and its update time
+fn validate_round(
+    round: &RoundDataResponse,
+    heartbeat: Option<u64>,
+    now: u64,
+) -> Result<(Uint128, u64), RoundError> {
+    let (answer, updated_at) = match (round.answer, round.updated_at) {
+        (Some(answer), Some(updated_at)) => (answer, updated_at),
+        _ => return Err(RoundError::MissingAnswer {}),
+    };
+
+    if round.answered_in_round < round.round_id {
+        return Err(RoundError::IncompleteRound {
+            round_id: round.round_id,
+            answered_in_round: round.answered_in_round,
+        });
+    }
+
+    if answer.is_zero() {
+        return Err(RoundError::ZeroAnswer {});
+    }
+
+    if let Some(heartbeat) = heartbeat {
+        if updated_at.saturating_add(heartbeat) < now {
+            return Err(RoundError::StaleAnswer {
+                updated_at,
+                heartbeat,
+            });
+        }
+    }
+
+    Ok((answer, updated_at))
+}
+
+/// Converts an aggregator answer with `decimals` decimal places to a decimal,
+/// failing if the result does not fit in a [`Decimal`]
+fn parse_answer(answer: Uint128, decimals: u8) -> Result<Decimal256, ContractError> {
+    if decimals > MAX_DECIMALS {
+        return Err(ContractError::ProxyError {
+            reason: format!("At most {} decimals are supported", MAX_DECIMALS),
+        });
+    }
+
+    let mut denominator = Uint256::one();
+    for _ in 0..decimals {
+        denominator = denominator * Uint256::from(10u64);
+    }
+
+    let rate: Decimal256 = Decimal256::from_ratio(Uint256::from(answer), denominator);
+    to_decimal(rate)?;
+
+    Ok(rate)
+}
+
+/// Converts a rate to the standard [`Decimal`] type, failing instead of panicking if it is out of range
+fn to_decimal(rate: Decimal256) -> Result<Decimal, ContractError> {
+    if rate > Decimal256::from(Decimal::MAX) {
+        return Err(ContractError::ProxyError {
+            reason: "Rate exceeds the maximum supported value".to_string(),
+        });
+    }
+
+    Ok(rate.into())
 }
 
 #[cfg(test)]
@@ -218,11 +782,46 @@ mod tests {
             answered_in_round: 205531,
         };
 
-        let parsed_rate: Decimal256 = Decimal256::from_ratio(
-            Uint256::from(res.answer.unwrap()),
-            Uint256::from(1e8 as u128),
-        );
+        let parsed_rate: Decimal256 = parse_answer(res.answer.unwrap(), 8u8).unwrap();
 
         assert_eq!(parsed_rate, Decimal256::from_str("3504.56000000").unwrap())
     }
+
+    #[test]
+    fn test_parse_chainlink_decimals() {
+        let answer = Uint128::from_str("1234500000000000000").unwrap();
+
+        assert_eq!(
+            parse_answer(answer, 18u8).unwrap(),
+            Decimal256::from_str("1.2345").unwrap()
+        );
+        assert_eq!(
+            parse_answer(answer, 0u8).unwrap(),
+            Decimal256::from_str("1234500000000000000").unwrap()
+        );
+        assert_eq!(
+            parse_answer(answer, MAX_DECIMALS).unwrap(),
+            Decimal256::from_str("0.000000000000000001").unwrap()
+        );
+
+        // more decimals than supported
+        assert_eq!(
+            parse_answer(answer, 78u8).unwrap_err(),
+            ContractError::ProxyError {
+                reason: "At most 36 decimals are supported".to_string()
+            }
+        );
+
+        // answer that does not fit in a Decimal
+        assert_eq!(
+            parse_answer(Uint128::MAX, 0u8).unwrap_err(),
+            ContractError::ProxyError {
+                reason: "Rate exceeds the maximum supported value".to_string()
+            }
+        );
+        assert_eq!(
+            to_decimal(Decimal256::from(Decimal::MAX)).unwrap(),
+            Decimal::MAX
+        );
+    }
 }
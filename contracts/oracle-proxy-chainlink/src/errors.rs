@@ -0,0 +1,57 @@
+use thiserror::Error;
+
+use crate::ContractError;
+
+/// Reasons why a round returned by a Chainlink aggregator is rejected.
+/// They are returned as [`ContractError::ProxyError`] so the hub falls through to the next source
+#[derive(Error, Debug, PartialEq)]
+pub enum RoundError {
+    #[error("Source did not return answer")]
+    MissingAnswer {},
+
+    #[error(
+        "Round {round_id} is incomplete, answer was carried over from round {answered_in_round}"
+    )]
+    IncompleteRound {
+        round_id: u32,
+        answered_in_round: u32,
+    },
+
+    #[error("Source returned a zero answer")]
+    ZeroAnswer {},
+
+    #[error("Answer updated at {updated_at} is older than the heartbeat of {heartbeat} seconds")]
+    StaleAnswer { updated_at: u64, heartbeat: u64 },
+}
+
+/// Reasons why prices are not served according to the uptime feed
+#[derive(Error, Debug, PartialEq)]
+pub enum UptimeError {
+    #[error("Uptime feed did not return a status")]
+    StatusNotAvailable {},
+
+    #[error("Uptime feed reports that the system is down")]
+    SystemDown {},
+
+    #[error("System recovered at {recovered_at}, prices are not served until the grace period ends at {grace_period_end}")]
+    GracePeriodNotOver {
+        recovered_at: u64,
+        grace_period_end: u64,
+    },
+}
+
+impl From<UptimeError> for ContractError {
+    fn from(err: UptimeError) -> Self {
+        ContractError::ProxyError {
+            reason: err.to_string(),
+        }
+    }
+}
+
+impl From<RoundError> for ContractError {
+    fn from(err: RoundError) -> Self {
+        ContractError::ProxyError {
+            reason: err.to_string(),
+        }
+    }
+}
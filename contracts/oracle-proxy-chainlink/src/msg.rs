@@ -1,4 +1,4 @@
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tefi_oracle::proxy::ProxyQueryMsg;
@@ -17,11 +17,46 @@ pub enum ExecuteMsg {
     /// Registers new sources, overwrites if already exists
     SetSources {
         sources: Vec<(String, String)>, // (symbol, source)
+        /// Sources derived from two aggregators
+        composite_sources: Option<Vec<(String, CompositeSourceMsg)>>,
     },
     /// Removes an existing source
     RemoveSource {
         symbol: String,
     },
+    /// Queries the decimals and description of the aggregators of the provided
+    /// symbols again and updates the cached values, used after a feed is upgraded
+    RefreshDecimals {
+        symbols: Vec<String>,
+    },
+    /// Sets the uptime or flag aggregator, where an answer of 0 means the system is up.
+    /// Prices are not served while it reports an outage or within `grace_period`
+    /// seconds after recovering. Disabled if `uptime_feed` is not provided
+    SetUptimeFeed {
+        uptime_feed: Option<String>,
+        grace_period: u64,
+    },
+    /// Sets the maximum age in seconds of the latest answer of the aggregators
+    /// of the provided symbols, `None` disables the check
+    SetHeartbeats {
+        heartbeats: Vec<(String, Option<u64>)>, // (symbol, heartbeat)
+    },
+}
+
+/// A source whose rate is computed as `base` multiplied or divided by `quote`,
+/// for example X/USD = X/ETH * ETH/USD
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CompositeSourceMsg {
+    pub base: String,
+    pub quote: String,
+    pub operation: Operation,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Multiply,
+    Divide,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -29,17 +64,71 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Base(ProxyQueryMsg),
     Config {},
-    Sources { symbol: Option<String> },
+    /// Returns the source of `symbol` if provided, otherwise a page of all sources
+    Sources {
+        symbol: Option<String>,
+        start_after: Option<String>, // symbol for pagination
+        limit: Option<u32>,
+    },
+    /// Price of the symbol reported in a specific round of its aggregator
+    PriceAtRound {
+        symbol: String,
+        round_id: u32,
+    },
+    /// Average price over the latest `rounds` rounds, each answer weighted
+    /// by how long it was active
+    RoundTwap {
+        symbol: String,
+        rounds: u32,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
+    pub uptime_feed: Option<String>,
+    pub grace_period: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct SourcesResponse {
-    pub sources: Vec<(String, String)>,
+    pub sources: Vec<SourceResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SourceResponse {
+    pub symbol: String,
+    /// the aggregator of a single source, or the base and quote aggregators of a composite source
+    pub aggregators: Vec<AggregatorResponse>,
+    /// only set for composite sources
+    pub operation: Option<Operation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AggregatorResponse {
+    pub address: String,
+    pub decimals: u8,
+    pub heartbeat: Option<u64>,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundPriceResponse {
+    pub round_id: u32,
+    pub rate: Decimal,
+    pub last_updated: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundTwapResponse {
+    pub rate: Decimal,
+    /// number of rounds included in the average, can be lower than requested
+    /// if older rounds are not available
+    pub rounds: u32,
+    /// update time of the oldest round included
+    pub start: u64,
+    /// update time of the latest round
+    pub last_updated: u64,
 }
 
 // Chainlink interfaces
@@ -1,23 +1,38 @@
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::de::{EnumAccess, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
 
 use cosmwasm_std::Addr;
 use cw_storage_plus::{Item, Map};
 
-use crate::msg::ConfigResponse;
+use crate::msg::{ConfigResponse, Operation};
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const SOURCES: Map<&[u8], Addr> = Map::new("sources");
+pub const SOURCES: Map<&[u8], Source> = Map::new("sources");
+// cached information of each aggregator, indexed by aggregator address
+pub const AGGREGATORS: Map<&[u8], AggregatorInfo> = Map::new("aggregators");
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Config {
     pub owner: Addr,
+    // prices are not served while the uptime feed reports an outage
+    pub uptime: Option<UptimeConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct UptimeConfig {
+    pub feed: Addr,
+    // seconds after recovering from an outage during which prices are still not served
+    pub grace_period: u64,
 }
 
 impl Config {
     pub fn as_res(&self) -> ConfigResponse {
         ConfigResponse {
             owner: self.owner.to_string(),
+            uptime_feed: self.uptime.as_ref().map(|uptime| uptime.feed.to_string()),
+            grace_period: self.uptime.as_ref().map(|uptime| uptime.grace_period),
         }
     }
 
@@ -26,3 +41,117 @@ impl Config {
         self.owner.eq(addr)
     }
 }
+
+#[derive(Serialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Single {
+        aggregator: Addr,
+    },
+    Composite {
+        base: Addr,
+        quote: Addr,
+        operation: Operation,
+    },
+}
+
+/// Sources registered before composite sources were supported are stored as the address
+/// of their aggregator, they are read back as single sources
+impl<'de> Deserialize<'de> for Source {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_enum("Source", &["single", "composite"], SourceVisitor)
+    }
+}
+
+enum SourceTag {
+    Single,
+    Composite,
+    Legacy(Addr),
+}
+
+impl<'de> Deserialize<'de> for SourceTag {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let tag: String = String::deserialize(deserializer)?;
+
+        Ok(match tag.as_str() {
+            "single" => SourceTag::Single,
+            "composite" => SourceTag::Composite,
+            _ => SourceTag::Legacy(Addr::unchecked(tag)),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct SingleSource {
+    aggregator: Addr,
+}
+
+#[derive(Deserialize)]
+struct CompositeSource {
+    base: Addr,
+    quote: Addr,
+    operation: Operation,
+}
+
+struct SourceVisitor;
+
+impl<'de> Visitor<'de> for SourceVisitor {
+    type Value = Source;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a price source")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (tag, variant) = data.variant::<SourceTag>()?;
+
+        match tag {
+            SourceTag::Single => {
+                let source: SingleSource = variant.newtype_variant()?;
+                Ok(Source::Single {
+                    aggregator: source.aggregator,
+                })
+            }
+            SourceTag::Composite => {
+                let source: CompositeSource = variant.newtype_variant()?;
+                Ok(Source::Composite {
+                    base: source.base,
+                    quote: source.quote,
+                    operation: source.operation,
+                })
+            }
+            SourceTag::Legacy(aggregator) => {
+                variant.unit_variant()?;
+                Ok(Source::Single { aggregator })
+            }
+        }
+    }
+}
+
+impl Source {
+    /// Returns the aggregators used by the source
+    pub fn aggregators(&self) -> Vec<&Addr> {
+        match self {
+            Source::Single { aggregator } => vec![aggregator],
+            Source::Composite { base, quote, .. } => vec![base, quote],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AggregatorInfo {
+    pub decimals: u8,
+    // maximum age in seconds of the latest answer, not checked if not set
+    pub heartbeat: Option<u64>,
+    // description reported by the aggregator, if it supports the query
+    pub description: Option<String>,
+}
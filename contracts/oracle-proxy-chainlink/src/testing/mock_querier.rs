@@ -0,0 +1,114 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use std::collections::HashMap;
+
+use crate::msg::{AggregatorQuery, AggregatorQueryMsg, RoundDataResponse};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        api: MockApi::default(),
+        storage: MockStorage::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    aggregators: HashMap<String, MockAggregator>,
+}
+
+#[derive(Clone, Default)]
+pub struct MockAggregator {
+    pub decimals: u8,
+    pub description: Option<String>,
+    // rounds sorted from oldest to latest
+    pub rounds: Vec<RoundDataResponse>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                let aggregator = match self.aggregators.get(contract_addr) {
+                    Some(aggregator) => aggregator,
+                    None => {
+                        return SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.to_string(),
+                        })
+                    }
+                };
+
+                let query: AggregatorQuery = from_binary(msg).unwrap();
+                let res = match query.aggregator_query {
+                    AggregatorQueryMsg::GetDecimals {} => to_binary(&aggregator.decimals),
+                    AggregatorQueryMsg::GetDescription {} => match &aggregator.description {
+                        Some(description) => to_binary(description),
+                        None => return no_data(msg.as_slice()),
+                    },
+                    AggregatorQueryMsg::GetLatestRoundData {} => match aggregator.rounds.last() {
+                        Some(round) => to_binary(round),
+                        None => return no_data(msg.as_slice()),
+                    },
+                    AggregatorQueryMsg::GetRoundData { round_id } => match aggregator
+                        .rounds
+                        .iter()
+                        .find(|round| round.round_id == round_id)
+                    {
+                        Some(round) => to_binary(round),
+                        None => return no_data(msg.as_slice()),
+                    },
+                    _ => return no_data(msg.as_slice()),
+                };
+
+                SystemResult::Ok(ContractResult::from(res))
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+fn no_data(request: &[u8]) -> QuerierResult {
+    SystemResult::Err(SystemError::InvalidRequest {
+        error: "No data available".to_string(),
+        request: request.into(),
+    })
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            aggregators: HashMap::new(),
+        }
+    }
+
+    // configure a mock aggregator contract
+    pub fn with_aggregator(&mut self, addr: &str, aggregator: MockAggregator) {
+        self.aggregators.insert(addr.to_string(), aggregator);
+    }
+}
@@ -0,0 +1,960 @@
+use crate::contract::{execute, instantiate, query};
+use crate::errors::{RoundError, UptimeError};
+use crate::msg::{
+    AggregatorResponse, CompositeSourceMsg, ConfigResponse, ExecuteMsg, InstantiateMsg, Operation,
+    QueryMsg, RoundDataResponse, RoundPriceResponse, RoundTwapResponse, SourceResponse,
+    SourcesResponse,
+};
+use crate::state::AGGREGATORS;
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi};
+use cosmwasm_std::{
+    from_binary, Addr, Decimal, MemoryStorage, OwnedDeps, Response, Timestamp, Uint128,
+};
+use cw_storage_plus::Map;
+use std::str::FromStr;
+use tefi_oracle::errors::ContractError;
+use tefi_oracle::proxy::{ProxyPriceResponse, ProxyQueryMsg};
+
+use super::mock_querier::{mock_dependencies, MockAggregator, WasmMockQuerier};
+
+const OWNER_ADDR: &str = "owner_0001";
+const AGGREGATOR_ADDR_1: &str = "aggregator_0001";
+const AGGREGATOR_ADDR_2: &str = "aggregator_0002";
+const AGGREGATOR_ADDR_3: &str = "aggregator_0003";
+const AGGREGATOR_ADDR_4: &str = "aggregator_0004";
+const UPTIME_FEED_ADDR: &str = "uptime_0001";
+
+// helper to successfully init
+pub fn init(
+    deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+) -> Result<Response, ContractError> {
+    let msg = InstantiateMsg {
+        owner: OWNER_ADDR.to_string(),
+    };
+    let info = mock_info(OWNER_ADDR, &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg)
+}
+
+// helper to build a round
+pub fn round(round_id: u32, answer: &str, updated_at: u64) -> RoundDataResponse {
+    RoundDataResponse {
+        round_id,
+        answer: Some(Uint128::from_str(answer).unwrap()),
+        started_at: Some(updated_at),
+        updated_at: Some(updated_at),
+        answered_in_round: round_id,
+    }
+}
+
+// helper to set sources
+pub fn set_sources(
+    deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+    sources: Vec<(&str, &str)>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::SetSources {
+        sources: sources
+            .into_iter()
+            .map(|(symbol, source)| (symbol.to_string(), source.to_string()))
+            .collect(),
+        composite_sources: None,
+    };
+    let info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg)
+}
+
+// helper to query the price of a symbol
+pub fn query_price(
+    deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+    symbol: &str,
+) -> Result<ProxyPriceResponse, ContractError> {
+    query_price_at(deps, symbol, mock_env().block.time.seconds())
+}
+
+// helper to query the price of a symbol at the given block time
+pub fn query_price_at(
+    deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+    symbol: &str,
+    now: u64,
+) -> Result<ProxyPriceResponse, ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(now);
+    let msg = QueryMsg::Base(ProxyQueryMsg::Price {
+        symbol: symbol.to_string(),
+    });
+    query(deps.as_ref(), env, msg).map(|res| from_binary(&res).unwrap())
+}
+
+#[test]
+fn test_set_sources_with_decimals() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![round(1, "350456000000", 1000)],
+            description: None,
+        },
+    );
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_2,
+        MockAggregator {
+            decimals: 18u8,
+            rounds: vec![round(1, "63250000000000000", 1000)],
+            description: None,
+        },
+    );
+
+    // unauthorized attempt
+    let msg = ExecuteMsg::SetSources {
+        sources: vec![("ETH".to_string(), AGGREGATOR_ADDR_1.to_string())],
+        composite_sources: None,
+    };
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // aggregator that does not answer
+    let err = set_sources(&mut deps, vec![("ETH", "notanaggregator")]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Failed to query latest round data from notanaggregator".to_string()
+        }
+    );
+
+    set_sources(
+        &mut deps,
+        vec![("ETH", AGGREGATOR_ADDR_1), ("LINK", AGGREGATOR_ADDR_2)],
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Sources {
+            symbol: None,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let sources_res: SourcesResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        sources_res,
+        SourcesResponse {
+            sources: vec![
+                SourceResponse {
+                    symbol: "ETH".to_string(),
+                    aggregators: vec![AggregatorResponse {
+                        address: AGGREGATOR_ADDR_1.to_string(),
+                        decimals: 8u8,
+                        heartbeat: None,
+                        description: None,
+                    }],
+                    operation: None,
+                },
+                SourceResponse {
+                    symbol: "LINK".to_string(),
+                    aggregators: vec![AggregatorResponse {
+                        address: AGGREGATOR_ADDR_2.to_string(),
+                        decimals: 18u8,
+                        heartbeat: None,
+                        description: None,
+                    }],
+                    operation: None,
+                }
+            ]
+        }
+    );
+
+    assert_eq!(
+        query_price(&deps, "ETH").unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("3504.56").unwrap(),
+            last_updated: 1000u64,
+        }
+    );
+    assert_eq!(
+        query_price(&deps, "LINK").unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("0.06325").unwrap(),
+            last_updated: 1000u64,
+        }
+    );
+}
+
+#[test]
+fn test_refresh_decimals() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![round(1, "350456000000", 1000)],
+            description: None,
+        },
+    );
+    set_sources(&mut deps, vec![("ETH", AGGREGATOR_ADDR_1)]).unwrap();
+
+    // the feed is upgraded and now reports 10 decimals
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 10u8,
+            rounds: vec![round(2, "35045600000000", 2000)],
+            description: None,
+        },
+    );
+    assert_eq!(
+        query_price(&deps, "ETH").unwrap().rate,
+        Decimal::from_str("350456").unwrap()
+    );
+
+    let msg = ExecuteMsg::RefreshDecimals {
+        symbols: vec!["ETH".to_string()],
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+    assert_eq!(
+        query_price(&deps, "ETH").unwrap().rate,
+        Decimal::from_str("3504.56").unwrap()
+    );
+
+    // symbol not registered
+    let msg = ExecuteMsg::RefreshDecimals {
+        symbols: vec!["BTC".to_string()],
+    };
+    let err = execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Price source not registered".to_string()
+        }
+    );
+
+    // aggregators registered before the decimals were cached query them directly
+    AGGREGATORS.remove(&mut deps.storage, AGGREGATOR_ADDR_1.as_bytes());
+    assert_eq!(
+        query_price(&deps, "ETH").unwrap().rate,
+        Decimal::from_str("3504.56").unwrap()
+    );
+
+    // the feed is upgraded to more decimals than supported
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 40u8,
+            rounds: vec![round(3, "35045600000000", 3000)],
+            description: None,
+        },
+    );
+    let msg = ExecuteMsg::RefreshDecimals {
+        symbols: vec!["ETH".to_string()],
+    };
+    let err = execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Aggregator aggregator_0001 reports 40 decimals, at most 36 are supported"
+                .to_string()
+        }
+    );
+    let err = set_sources(&mut deps, vec![("BTC", AGGREGATOR_ADDR_1)]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Aggregator aggregator_0001 reports 40 decimals, at most 36 are supported"
+                .to_string()
+        }
+    );
+}
+
+#[test]
+fn test_round_validation() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![round(1, "350456000000", 1000)],
+            description: None,
+        },
+    );
+    set_sources(&mut deps, vec![("ETH", AGGREGATOR_ADDR_1)]).unwrap();
+
+    // no heartbeat, any answer age is accepted
+    assert_eq!(
+        query_price_at(&deps, "ETH", 1_000_000)
+            .unwrap()
+            .last_updated,
+        1000u64
+    );
+
+    let msg = ExecuteMsg::SetHeartbeats {
+        heartbeats: vec![("ETH".to_string(), Some(3600u64))],
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Sources {
+            symbol: Some("ETH".to_string()),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let sources_res: SourcesResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        sources_res.sources[0].aggregators[0].heartbeat,
+        Some(3600u64)
+    );
+
+    // within the heartbeat
+    assert_eq!(
+        query_price_at(&deps, "ETH", 4600).unwrap().rate,
+        Decimal::from_str("3504.56").unwrap()
+    );
+
+    // older than the heartbeat
+    let err = query_price_at(&deps, "ETH", 4601).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::from(RoundError::StaleAnswer {
+            updated_at: 1000u64,
+            heartbeat: 3600u64,
+        })
+    );
+
+    // refreshing the decimals keeps the heartbeat
+    let msg = ExecuteMsg::RefreshDecimals {
+        symbols: vec!["ETH".to_string()],
+    };
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+    assert!(query_price_at(&deps, "ETH", 4601).is_err());
+
+    // answer carried over from a previous round
+    let mut carried_over = round(2, "350456000000", 2000);
+    carried_over.answered_in_round = 1;
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![round(1, "350456000000", 1000), carried_over],
+            description: None,
+        },
+    );
+    let err = query_price_at(&deps, "ETH", 2000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Round 2 is incomplete, answer was carried over from round 1".to_string()
+        }
+    );
+
+    // zero answer
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![round(3, "0", 3000)],
+            description: None,
+        },
+    );
+    let err = query_price_at(&deps, "ETH", 3000).unwrap_err();
+    assert_eq!(err, ContractError::from(RoundError::ZeroAnswer {}));
+
+    // missing answer
+    let mut missing = round(4, "350456000000", 4000);
+    missing.answer = None;
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![missing],
+            description: None,
+        },
+    );
+    let err = query_price_at(&deps, "ETH", 4000).unwrap_err();
+    assert_eq!(err, ContractError::from(RoundError::MissingAnswer {}));
+}
+
+#[test]
+fn test_round_queries() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![
+                round(1, "1000000000", 1000),
+                round(2, "2000000000", 2000),
+                round(3, "4000000000", 4000),
+            ],
+            description: None,
+        },
+    );
+    set_sources(&mut deps, vec![("ETH", AGGREGATOR_ADDR_1)]).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(5000);
+
+    let msg = QueryMsg::PriceAtRound {
+        symbol: "ETH".to_string(),
+        round_id: 2,
+    };
+    let res: RoundPriceResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        RoundPriceResponse {
+            round_id: 2,
+            rate: Decimal::from_str("20").unwrap(),
+            last_updated: 2000u64,
+        }
+    );
+
+    let msg = QueryMsg::PriceAtRound {
+        symbol: "ETH".to_string(),
+        round_id: 7,
+    };
+    let err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Round 7 not available".to_string()
+        }
+    );
+
+    // 10 for 1000s, 20 for 2000s and 40 for 1000s until the current block
+    let msg = QueryMsg::RoundTwap {
+        symbol: "ETH".to_string(),
+        rounds: 3,
+    };
+    let res: RoundTwapResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        RoundTwapResponse {
+            rate: Decimal::from_str("22.5").unwrap(),
+            rounds: 3,
+            start: 1000u64,
+            last_updated: 4000u64,
+        }
+    );
+
+    let msg = QueryMsg::RoundTwap {
+        symbol: "ETH".to_string(),
+        rounds: 2,
+    };
+    let res: RoundTwapResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.rate,
+        Decimal::from_str("26.666666666666666666").unwrap()
+    );
+    assert_eq!(res.start, 2000u64);
+
+    // only the available rounds are included
+    let msg = QueryMsg::RoundTwap {
+        symbol: "ETH".to_string(),
+        rounds: 10,
+    };
+    let res: RoundTwapResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.rounds, 3);
+    assert_eq!(res.rate, Decimal::from_str("22.5").unwrap());
+
+    // latest round updated at the current block
+    env.block.time = Timestamp::from_seconds(4000);
+    let msg = QueryMsg::RoundTwap {
+        symbol: "ETH".to_string(),
+        rounds: 1,
+    };
+    let res: RoundTwapResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("40").unwrap());
+
+    let msg = QueryMsg::RoundTwap {
+        symbol: "ETH".to_string(),
+        rounds: 0,
+    };
+    let err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Number of rounds must be between 1 and 50".to_string()
+        }
+    );
+
+    // the average stops at an earlier round whose answer was carried over
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![
+                round(1, "1000000000", 1000),
+                RoundDataResponse {
+                    answered_in_round: 1,
+                    ..round(2, "1000000000", 2000)
+                },
+                round(3, "4000000000", 4000),
+                round(4, "2000000000", 4500),
+            ],
+            description: None,
+        },
+    );
+    env.block.time = Timestamp::from_seconds(5000);
+    let msg = QueryMsg::RoundTwap {
+        symbol: "ETH".to_string(),
+        rounds: 4,
+    };
+    let res: RoundTwapResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        RoundTwapResponse {
+            rate: Decimal::from_str("30").unwrap(),
+            rounds: 2,
+            start: 4000u64,
+            last_updated: 4500u64,
+        }
+    );
+}
+
+#[test]
+fn test_composite_sources() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    // LINK/ETH with 18 decimals
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 18u8,
+            rounds: vec![round(1, "5000000000000000", 1000)],
+            description: None,
+        },
+    );
+    // ETH/USD with 8 decimals
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_2,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![round(1, "400000000000", 2000)],
+            description: None,
+        },
+    );
+
+    let msg = ExecuteMsg::SetSources {
+        sources: vec![("ETH".to_string(), AGGREGATOR_ADDR_2.to_string())],
+        composite_sources: Some(vec![
+            (
+                "LINK".to_string(),
+                CompositeSourceMsg {
+                    base: AGGREGATOR_ADDR_1.to_string(),
+                    quote: AGGREGATOR_ADDR_2.to_string(),
+                    operation: Operation::Multiply,
+                },
+            ),
+            (
+                "LINK/ETH".to_string(),
+                CompositeSourceMsg {
+                    base: AGGREGATOR_ADDR_1.to_string(),
+                    quote: AGGREGATOR_ADDR_1.to_string(),
+                    operation: Operation::Divide,
+                },
+            ),
+        ]),
+    };
+    let info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the older update time of both aggregators is reported
+    assert_eq!(
+        query_price(&deps, "LINK").unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("20").unwrap(),
+            last_updated: 1000u64,
+        }
+    );
+    assert_eq!(query_price(&deps, "LINK/ETH").unwrap().rate, Decimal::one());
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Sources {
+            symbol: Some("LINK".to_string()),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let sources_res: SourcesResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        sources_res.sources,
+        vec![SourceResponse {
+            symbol: "LINK".to_string(),
+            aggregators: vec![
+                AggregatorResponse {
+                    address: AGGREGATOR_ADDR_1.to_string(),
+                    decimals: 18u8,
+                    heartbeat: None,
+                    description: None,
+                },
+                AggregatorResponse {
+                    address: AGGREGATOR_ADDR_2.to_string(),
+                    decimals: 8u8,
+                    heartbeat: None,
+                    description: None,
+                }
+            ],
+            operation: Some(Operation::Multiply),
+        }]
+    );
+
+    // a stale leg rejects the composite price
+    let msg = ExecuteMsg::SetHeartbeats {
+        heartbeats: vec![("ETH".to_string(), Some(1500u64))],
+    };
+    let info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert!(query_price_at(&deps, "LINK", 3000).is_ok());
+    let err = query_price_at(&deps, "LINK", 3501).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::from(RoundError::StaleAnswer {
+            updated_at: 2000u64,
+            heartbeat: 1500u64,
+        })
+    );
+
+    // round queries need a single aggregator
+    let msg = QueryMsg::PriceAtRound {
+        symbol: "LINK".to_string(),
+        round_id: 1,
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Round queries are not supported for composite sources".to_string()
+        }
+    );
+
+    // quote rate that rounds down to zero
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_3,
+        MockAggregator {
+            decimals: 36u8,
+            rounds: vec![round(1, "1", 1000)],
+            description: None,
+        },
+    );
+    // rate close to the maximum supported value
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_4,
+        MockAggregator {
+            decimals: 0u8,
+            rounds: vec![round(1, "100000000000000000000", 1000)],
+            description: None,
+        },
+    );
+    let msg = ExecuteMsg::SetSources {
+        sources: vec![],
+        composite_sources: Some(vec![
+            (
+                "ZERO".to_string(),
+                CompositeSourceMsg {
+                    base: AGGREGATOR_ADDR_1.to_string(),
+                    quote: AGGREGATOR_ADDR_3.to_string(),
+                    operation: Operation::Divide,
+                },
+            ),
+            (
+                "LARGE".to_string(),
+                CompositeSourceMsg {
+                    base: AGGREGATOR_ADDR_4.to_string(),
+                    quote: AGGREGATOR_ADDR_4.to_string(),
+                    operation: Operation::Multiply,
+                },
+            ),
+        ]),
+    };
+    let info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let err = query_price(&deps, "ZERO").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Quote aggregator returned a zero rate".to_string()
+        }
+    );
+    let err = query_price(&deps, "LARGE").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Rate exceeds the maximum supported value".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_legacy_sources() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            rounds: vec![round(1, "350456000000", 1000)],
+            description: None,
+        },
+    );
+
+    // sources registered before composite sources were supported only stored the aggregator
+    let legacy_sources: Map<&[u8], Addr> = Map::new("sources");
+    legacy_sources
+        .save(
+            &mut deps.storage,
+            b"ETH",
+            &Addr::unchecked(AGGREGATOR_ADDR_1),
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_price(&deps, "ETH").unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("3504.56").unwrap(),
+            last_updated: 1000u64,
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Sources {
+            symbol: None,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let sources_res: SourcesResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        sources_res.sources,
+        vec![SourceResponse {
+            symbol: "ETH".to_string(),
+            aggregators: vec![AggregatorResponse {
+                address: AGGREGATOR_ADDR_1.to_string(),
+                decimals: 8u8,
+                heartbeat: None,
+                description: None,
+            }],
+            operation: None,
+        }]
+    );
+}
+
+#[test]
+fn test_sources_validation_and_pagination() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            description: Some("ETH / USD".to_string()),
+            rounds: vec![round(1, "350456000000", 1000)],
+        },
+    );
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_2,
+        MockAggregator {
+            decimals: 8u8,
+            description: None,
+            rounds: vec![],
+        },
+    );
+
+    // aggregator without rounds
+    let err = set_sources(&mut deps, vec![("LINK", AGGREGATOR_ADDR_2)]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: format!(
+                "Failed to query latest round data from {}",
+                AGGREGATOR_ADDR_2
+            )
+        }
+    );
+
+    // aggregator with an invalid latest round
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_2,
+        MockAggregator {
+            decimals: 8u8,
+            description: None,
+            rounds: vec![round(1, "0", 1000)],
+        },
+    );
+    let err = set_sources(&mut deps, vec![("LINK", AGGREGATOR_ADDR_2)]).unwrap_err();
+    assert_eq!(err, ContractError::from(RoundError::ZeroAnswer {}));
+
+    set_sources(
+        &mut deps,
+        vec![
+            ("BTC", AGGREGATOR_ADDR_1),
+            ("ETH", AGGREGATOR_ADDR_1),
+            ("LUNA", AGGREGATOR_ADDR_1),
+        ],
+    )
+    .unwrap();
+
+    let msg = QueryMsg::Sources {
+        symbol: None,
+        start_after: None,
+        limit: Some(2),
+    };
+    let sources_res: SourcesResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    let symbols: Vec<String> = sources_res
+        .sources
+        .iter()
+        .map(|source| source.symbol.clone())
+        .collect();
+    assert_eq!(symbols, vec!["BTC".to_string(), "ETH".to_string()]);
+    assert_eq!(
+        sources_res.sources[0].aggregators[0].description,
+        Some("ETH / USD".to_string())
+    );
+
+    let msg = QueryMsg::Sources {
+        symbol: None,
+        start_after: Some("ETH".to_string()),
+        limit: None,
+    };
+    let sources_res: SourcesResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    let symbols: Vec<String> = sources_res
+        .sources
+        .iter()
+        .map(|source| source.symbol.clone())
+        .collect();
+    assert_eq!(symbols, vec!["LUNA".to_string()]);
+}
+
+#[test]
+fn test_uptime_feed() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    deps.querier.with_aggregator(
+        AGGREGATOR_ADDR_1,
+        MockAggregator {
+            decimals: 8u8,
+            description: None,
+            rounds: vec![round(1, "350456000000", 1000)],
+        },
+    );
+    // system recovered at 2000
+    deps.querier.with_aggregator(
+        UPTIME_FEED_ADDR,
+        MockAggregator {
+            decimals: 0u8,
+            description: None,
+            rounds: vec![round(1, "1", 1500), round(2, "0", 2000)],
+        },
+    );
+    set_sources(&mut deps, vec![("ETH", AGGREGATOR_ADDR_1)]).unwrap();
+
+    let msg = ExecuteMsg::SetUptimeFeed {
+        uptime_feed: Some(UPTIME_FEED_ADDR.to_string()),
+        grace_period: 3600u64,
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config_res,
+        ConfigResponse {
+            owner: OWNER_ADDR.to_string(),
+            uptime_feed: Some(UPTIME_FEED_ADDR.to_string()),
+            grace_period: Some(3600u64),
+        }
+    );
+
+    // inside the grace period
+    let err = query_price_at(&deps, "ETH", 5599).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::from(UptimeError::GracePeriodNotOver {
+            recovered_at: 2000u64,
+            grace_period_end: 5600u64,
+        })
+    );
+    assert!(query_price_at(&deps, "ETH", 5600).is_ok());
+
+    // system is down
+    deps.querier.with_aggregator(
+        UPTIME_FEED_ADDR,
+        MockAggregator {
+            decimals: 0u8,
+            description: None,
+            rounds: vec![round(3, "1", 6000)],
+        },
+    );
+    let err = query_price_at(&deps, "ETH", 7000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Uptime feed reports that the system is down".to_string()
+        }
+    );
+    let msg = QueryMsg::RoundTwap {
+        symbol: "ETH".to_string(),
+        rounds: 1,
+    };
+    let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::from(UptimeError::SystemDown {}));
+
+    // disabled
+    let msg = ExecuteMsg::SetUptimeFeed {
+        uptime_feed: None,
+        grace_period: 0u64,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+    assert!(query_price_at(&deps, "ETH", 7000).is_ok());
+}
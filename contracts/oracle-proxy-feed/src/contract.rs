@@ -1,20 +1,49 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Addr, Attribute, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    attr, to_binary, to_vec, Addr, Attribute, Binary, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, QuerierWrapper, QueryRequest, Response, StdResult, Storage, WasmQuery,
 };
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use tefi_oracle::de::deserialize_key;
+use tefi_oracle::history::{
+    load_observations, push_observation, resize_history, time_weighted_average, Observation,
+};
+use tefi_oracle::hub::{TwapResponse, MAX_HISTORY_SIZE};
 use tefi_oracle::proxy::{ProxyPriceResponse, ProxyQueryMsg};
 
-use crate::msg::{ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, QueryMsg};
-use crate::state::{Config, PriceInfo, CONFIG, FEEDERS, PRICES};
+use crate::errors::{ReportError, RevealError, SubmissionError};
+use crate::math::{median, weighted_median};
+use crate::msg::{
+    CommitRevealMsg, ConfigResponse, CurrentRoundResponse, Cw4QueryMsg, DelegateResponse,
+    DelegatesResponse, ExecuteMsg, FeedMsg, FeedPrice, FeedResponse, FeederFeedResponse,
+    FeederKeyResponse, FeederLivenessResponse, FeederResponse, FeedersByAddressResponse,
+    FeedsResponse, GuardsResponse, HistoryResponse, InstantiateMsg, LivenessResponse,
+    MemberListResponse, MemberResponse, MigrateMsg, ObservationResponse, PriceDetailResponse,
+    PriceReport, QueryMsg, RevealMsg, RoundResponse, RoundResultResponse, SignedPriceReport,
+    SubmissionResponse, SubmissionsResponse,
+};
+use crate::state::{
+    Commit, CommitReveal, Config, Delegation, Feed, FeedGuards, FinalizedPrice, PriceInfo, Round,
+    RoundResult, COMMITS, COMMIT_REVEAL, CONFIG, DELEGATES, FEEDER_DELEGATES, FEEDER_FEEDS,
+    FEEDER_KEYS, FEEDS, FINALIZED_PRICES, GUARDS, LAST_ROUNDS, LEGACY_FEEDERS, LEGACY_PRICES,
+    NONCES, PRICE_TTLS, ROUNDS, SUBMISSIONS,
+};
 use crate::ContractError;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "tefi-oracle-proxy-feed";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const DEFAULT_PAGINATION_LIMIT: u32 = 10u32;
+const MAX_PAGINATION_LIMIT: u32 = 30u32;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -26,12 +55,46 @@ pub fn instantiate(
 
     let config = Config {
         owner: deps.api.addr_validate(&msg.owner)?,
+        submission_window: msg.submission_window,
+        max_observation_age: msg.max_observation_age,
     };
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default())
 }
 
+/// Moves the feeder and price of each symbol registered by the single feeder version
+/// into a feed of that feeder with one required submission
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let legacy_feeders: Vec<(Vec<u8>, Addr)> = LEGACY_FEEDERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    for (symbol, feeder) in legacy_feeders {
+        FEEDS.save(
+            deps.storage,
+            &symbol,
+            &Feed {
+                feeders: vec![feeder.clone()],
+                min_submissions: 1,
+                group: None,
+                weighted: false,
+            },
+        )?;
+        FEEDER_FEEDS.save(deps.storage, (feeder.as_bytes(), &symbol), &Empty {})?;
+        if let Some(price) = LEGACY_PRICES.may_load(deps.storage, &symbol)? {
+            SUBMISSIONS.save(deps.storage, (&symbol, feeder.as_bytes()), &price)?;
+        }
+
+        LEGACY_FEEDERS.remove(deps.storage, &symbol);
+        LEGACY_PRICES.remove(deps.storage, &symbol);
+    }
+
+    Ok(Response::new().add_attribute("action", "migrate"))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -41,19 +104,94 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::UpdateOwner { owner } => update_owner(deps, info, owner),
-        ExecuteMsg::RegisterFeed { symbol, feeder } => register_feed(deps, info, symbol, feeder),
-        ExecuteMsg::FeedPrices { prices } => feed_prices(deps, env, info, prices),
+        ExecuteMsg::UpdateSubmissionWindow { submission_window } => {
+            update_submission_window(deps, info, submission_window)
+        }
+        ExecuteMsg::UpdateMaxObservationAge {
+            max_observation_age,
+        } => update_max_observation_age(deps, info, max_observation_age),
+        ExecuteMsg::SetFeederKeys { keys } => set_feeder_keys(deps, info, keys),
+        ExecuteMsg::RegisterFeed {
+            symbol,
+            feeders,
+            min_submissions,
+        } => register_feed(deps, info, symbol, feeders, min_submissions),
+        ExecuteMsg::RegisterGroupFeed {
+            symbol,
+            group,
+            min_submissions,
+            weighted,
+        } => register_group_feed(deps, info, symbol, group, min_submissions, weighted),
+        ExecuteMsg::BulkRegisterFeeds { feeds } => bulk_register_feeds(deps, info, feeds),
+        ExecuteMsg::RemoveFeed { symbol } => remove_feed(deps, info, symbol),
+        ExecuteMsg::UpdateHistorySize { symbol, size } => {
+            update_history_size(deps, info, symbol, size)
+        }
+        ExecuteMsg::UpdateGuards {
+            symbol,
+            min_update_interval,
+            max_change_bps,
+            min_price,
+            max_price,
+        } => update_guards(
+            deps,
+            info,
+            symbol,
+            FeedGuards {
+                min_update_interval,
+                max_change_bps,
+                min_price,
+                max_price,
+            },
+        ),
+        ExecuteMsg::UpdatePriceTtl { symbol, ttl } => update_price_ttl(deps, info, symbol, ttl),
+        ExecuteMsg::FeedPrices {
+            prices,
+            timestamped_prices,
+        } => feed_prices(deps, env, info, prices, timestamped_prices),
+        ExecuteMsg::AuthorizeDelegate { delegate, expires } => {
+            authorize_delegate(deps, env, info, delegate, expires)
+        }
+        ExecuteMsg::UpdateCommitReveal {
+            symbol,
+            commit_reveal,
+        } => update_commit_reveal(deps, info, symbol, commit_reveal),
+        ExecuteMsg::CommitPrices { commits } => commit_prices(deps, env, info, commits),
+        ExecuteMsg::RevealPrices { reveals } => reveal_prices(deps, env, info, reveals),
+        ExecuteMsg::FinalizeRound { symbol } => finalize_round(deps, env, symbol),
+        ExecuteMsg::RevokeDelegate { delegate } => revoke_delegate(deps, info, delegate),
+        ExecuteMsg::SubmitSignedPrices { reports } => submit_signed_prices(deps, env, reports),
+        ExecuteMsg::ForceUpdatePrices { prices } => force_update_prices(deps, env, info, prices),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     let res = match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Feeder { symbol } => to_binary(&query_feeder(deps, symbol)?),
+        QueryMsg::Feeds { start_after, limit } => {
+            to_binary(&query_feeds(deps, start_after, limit)?)
+        }
+        QueryMsg::FeedersByAddress { feeder } => {
+            to_binary(&query_feeders_by_address(deps, feeder)?)
+        }
+        QueryMsg::History {
+            symbol,
+            start_after,
+            limit,
+        } => to_binary(&query_history(deps, symbol, start_after, limit)?),
+        QueryMsg::Twap { symbol, window } => to_binary(&query_twap(deps, env, symbol, window)?),
+        QueryMsg::Submissions { symbol } => to_binary(&query_submissions(deps, symbol)?),
+        QueryMsg::PriceDetail { symbol } => to_binary(&query_price_detail(deps, env, symbol)?),
+        QueryMsg::Guards { symbol } => to_binary(&query_guards(deps, symbol)?),
+        QueryMsg::FeederKey { feeder } => to_binary(&query_feeder_key(deps, feeder)?),
+        QueryMsg::Round { symbol } => to_binary(&query_round(deps, symbol)?),
+        QueryMsg::Delegates { feeder } => to_binary(&query_delegates(deps, feeder)?),
+        QueryMsg::Liveness {} => to_binary(&query_liveness(deps, env)?),
         // Implementation of the queries required by proxy contract standard
         QueryMsg::Base(proxy_msg) => match proxy_msg {
-            ProxyQueryMsg::Price { symbol } => to_binary(&query_price(deps, symbol)?),
+            ProxyQueryMsg::Price { symbol } => to_binary(&query_price(deps, env, symbol)?),
         },
     };
 
@@ -84,12 +222,117 @@ pub fn update_owner(
     Ok(Response::default())
 }
 
-/// Registers a new `feeder` or updates an existing one for the specified `symbol`
+/// Updates the `submission_window`
+pub fn update_submission_window(
+    deps: DepsMut,
+    info: MessageInfo,
+    submission_window: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.submission_window = submission_window;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+/// Updates the `max_observation_age`
+pub fn update_max_observation_age(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_observation_age: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.max_observation_age = max_observation_age;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+/// Sets or removes the public keys used to verify the signed reports of the feeders
+/// ## Parameters
+/// * `keys` - Array of (`feeder`, `public_key`)
+pub fn set_feeder_keys(
+    deps: DepsMut,
+    info: MessageInfo,
+    keys: Vec<(String, Option<Binary>)>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (feeder, public_key) in keys {
+        let feeder: Addr = deps.api.addr_validate(&feeder)?;
+        match public_key {
+            Some(public_key) => {
+                if !is_valid_public_key(&public_key) {
+                    return Err(ContractError::ProxyError {
+                        reason: format!("Invalid public key for feeder {}", feeder),
+                    });
+                }
+
+                // a key can only sign the reports of one feeder
+                if let Some(other) = key_feeder(deps.storage, &public_key)? {
+                    if other != feeder.as_str() {
+                        return Err(ContractError::ProxyError {
+                            reason: format!(
+                                "Public key is already registered for feeder {}",
+                                other
+                            ),
+                        });
+                    }
+                }
+
+                FEEDER_KEYS.save(deps.storage, feeder.as_bytes(), &public_key)?;
+            }
+            None => FEEDER_KEYS.remove(deps.storage, feeder.as_bytes()),
+        }
+    }
+
+    Ok(Response::default())
+}
+
+/// Registers the `feeders` of a new `symbol` or replaces the feeders of an existing one
 pub fn register_feed(
     deps: DepsMut,
     info: MessageInfo,
     symbol: String,
-    feeder: String,
+    feeders: Vec<String>,
+    min_submissions: u32,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    save_feed(deps, &symbol, feeders, min_submissions)?;
+
+    Ok(Response::default())
+}
+
+/// Registers `symbol` with the members of the cw4 `group` as feeders, or replaces the feeders of
+/// an existing one. The submissions of addresses that are not members are removed
+pub fn register_group_feed(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    group: String,
+    min_submissions: u32,
+    weighted: bool,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
@@ -97,87 +340,1644 @@ pub fn register_feed(
         return Err(ContractError::Unauthorized {});
     }
 
-    let feeder: Addr = deps.api.addr_validate(&feeder)?;
+    let group: Addr = deps.api.addr_validate(&group)?;
+    let members: Vec<String> =
+        load_group_members(&deps.querier, &group).map_err(|_| ContractError::ProxyError {
+            reason: format!("Failed to query the members of group {}", group),
+        })?;
+
+    if min_submissions == 0 || min_submissions as usize > members.len() {
+        return Err(ContractError::ProxyError {
+            reason: "Minimum submissions must be between 1 and the number of members".to_string(),
+        });
+    }
+
+    if let Some(previous) = FEEDS.may_load(deps.storage, symbol.as_bytes())? {
+        for feeder in previous.feeders {
+            FEEDER_FEEDS.remove(deps.storage, (feeder.as_bytes(), symbol.as_bytes()));
+        }
+    }
+    for (feeder, _) in load_submissions(deps.storage, &symbol)? {
+        if !members.contains(&feeder) {
+            SUBMISSIONS.remove(deps.storage, (symbol.as_bytes(), feeder.as_bytes()));
+        }
+    }
 
     // overwrite if exists
-    FEEDERS.save(deps.storage, symbol.as_bytes(), &feeder)?;
+    FEEDS.save(
+        deps.storage,
+        symbol.as_bytes(),
+        &Feed {
+            feeders: vec![],
+            min_submissions,
+            group: Some(group),
+            weighted,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+/// Registers or replaces the feeders of multiple symbols at once
+pub fn bulk_register_feeds(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    feeds: Vec<FeedMsg>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for feed in feeds {
+        save_feed(
+            deps.branch(),
+            &feed.symbol,
+            feed.feeders,
+            feed.min_submissions,
+        )?;
+    }
+
+    Ok(Response::default())
+}
+
+/// Removes a feed with its guards and the submissions of its feeders
+pub fn remove_feed(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let feed: Feed = load_feed(deps.as_ref(), &symbol)?;
+    for feeder in feed.feeders {
+        FEEDER_FEEDS.remove(deps.storage, (feeder.as_bytes(), symbol.as_bytes()));
+    }
+    for (feeder, _) in load_submissions(deps.storage, &symbol)? {
+        SUBMISSIONS.remove(deps.storage, (symbol.as_bytes(), feeder.as_bytes()));
+    }
+    GUARDS.remove(deps.storage, symbol.as_bytes());
+    PRICE_TTLS.remove(deps.storage, symbol.as_bytes());
+    clear_round(deps.storage, &symbol)?;
+    COMMIT_REVEAL.remove(deps.storage, symbol.as_bytes());
+    LAST_ROUNDS.remove(deps.storage, symbol.as_bytes());
+    FINALIZED_PRICES.remove(deps.storage, symbol.as_bytes());
+    resize_history(deps.storage, &symbol, 0)?;
+    FEEDS.remove(deps.storage, symbol.as_bytes());
+
+    Ok(Response::default())
+}
+
+/// Updates the number of prices kept in the history of `symbol`,
+/// a `size` of 0 disables and clears the history
+pub fn update_history_size(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    size: u32,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if size > MAX_HISTORY_SIZE {
+        return Err(ContractError::InvalidHistorySize {
+            max: MAX_HISTORY_SIZE,
+        });
+    }
+
+    load_feed(deps.as_ref(), &symbol)?;
+    resize_history(deps.storage, &symbol, size)?;
+
+    Ok(Response::default())
+}
+
+/// Sets the guards of a registered `symbol`
+pub fn update_guards(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    guards: FeedGuards,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    load_feed(deps.as_ref(), &symbol)?;
+
+    if let (Some(min_price), Some(max_price)) = (guards.min_price, guards.max_price) {
+        if min_price > max_price {
+            return Err(ContractError::ProxyError {
+                reason: "Minimum price can not be greater than maximum price".to_string(),
+            });
+        }
+    }
+
+    GUARDS.save(deps.storage, symbol.as_bytes(), &guards)?;
+
+    Ok(Response::default())
+}
+
+/// Sets the number of seconds after which the submissions of a registered `symbol` expire
+pub fn update_price_ttl(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    ttl: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    load_feed(deps.as_ref(), &symbol)?;
+
+    match ttl {
+        Some(0) => {
+            return Err(ContractError::ProxyError {
+                reason: "Price ttl must be greater than zero".to_string(),
+            })
+        }
+        Some(ttl) => PRICE_TTLS.save(deps.storage, symbol.as_bytes(), &ttl)?,
+        None => PRICE_TTLS.remove(deps.storage, symbol.as_bytes()),
+    }
 
     Ok(Response::default())
 }
 
-/// Feeder operation to feed prices to one or multiple asset tokens
+/// Feeder operation to feed prices to one or multiple asset tokens, also used by delegates
+/// on behalf of their feeder. Prices that violate the guards of their symbol or have an
+/// invalid observation timestamp are skipped
 /// ## Parameters
-/// * `prices` - Array of (`symbol`, `price`)
+/// * `prices` - Array of (`symbol`, `price`) observed at the block time
+/// * `timestamped_prices` - Prices with their observation time
 pub fn feed_prices(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     prices: Vec<(String, Decimal)>,
+    timestamped_prices: Option<Vec<FeedPrice>>,
 ) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let now: u64 = env.block.time.seconds();
+
+    let delegator: Option<Addr> = load_delegator(deps.storage, &info.sender, now)?;
+
+    let prices = prices
+        .into_iter()
+        .map(|(symbol, price)| FeedPrice {
+            symbol,
+            price,
+            timestamp: now,
+        })
+        .chain(timestamped_prices.unwrap_or_default());
+
     let mut attributes: Vec<Attribute> = vec![attr("action", "price_feed")];
     for price in prices {
-        attributes.push(attr("symbol", price.0.to_string()));
-        attributes.push(attr("price", price.1.to_string()));
-
         // Check feeder permission
-        let registered_feeder: Addr =
-            FEEDERS
-                .load(deps.storage, price.0.as_bytes())
-                .map_err(|_| ContractError::ProxyError {
-                    reason: "There is no feeder registered for the provided symbol".to_string(),
-                })?;
-
-        if registered_feeder.ne(&info.sender) {
-            return Err(ContractError::Unauthorized {});
-        }
+        let feed: Feed = load_feed(deps.as_ref(), &price.symbol)?;
+        let feeder: &Addr = feeder_for(&deps.querier, &feed, &info.sender, &delegator)?;
 
-        PRICES.save(
+        submit_price(
             deps.storage,
-            price.0.as_bytes(),
-            &PriceInfo {
-                price: price.1,
-                last_updated_time: env.block.time.seconds(),
+            &deps.querier,
+            &config,
+            &price.symbol,
+            feeder,
+            PriceInfo {
+                price: price.price,
+                last_updated_time: price.timestamp,
+                submitted_time: now,
             },
+            &mut attributes,
         )?;
     }
 
     Ok(Response::new().add_attributes(attributes))
 }
 
-////////////////////////////////////////////////////////////////////////////////////////////////
-/// Query implementations
-////////////////////////////////////////////////////////////////////////////////////////////////
+/// Feeder operation to authorize `delegate` to submit prices for all the symbols of the feeder
+pub fn authorize_delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegate: String,
+    expires: Option<u64>,
+) -> Result<Response, ContractError> {
+    let now: u64 = env.block.time.seconds();
 
-/// Queries the contract configuration
-pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    // only registered feeders and members of the group feeds can authorize delegates
+    let is_feeder: bool = FEEDER_FEEDS
+        .prefix(info.sender.as_bytes())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if !is_feeder && !is_group_feeder(deps.as_ref(), &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    Ok(config.as_res())
+    let delegate: Addr = deps.api.addr_validate(&delegate)?;
+    if delegate == info.sender {
+        return Err(ContractError::ProxyError {
+            reason: "A feeder can not delegate to itself".to_string(),
+        });
+    }
+
+    if let Some(expires) = expires {
+        if expires <= now {
+            return Err(ContractError::ProxyError {
+                reason: "Delegation expiry must be in the future".to_string(),
+            });
+        }
+    }
+
+    if let Some(delegation) = DELEGATES.may_load(deps.storage, delegate.as_bytes())? {
+        if delegation.feeder != info.sender && delegation.is_active(now) {
+            return Err(ContractError::ProxyError {
+                reason: format!(
+                    "Delegate {} is already authorized by another feeder",
+                    delegate
+                ),
+            });
+        }
+        FEEDER_DELEGATES.remove(
+            deps.storage,
+            (delegation.feeder.as_bytes(), delegate.as_bytes()),
+        );
+    }
+
+    DELEGATES.save(
+        deps.storage,
+        delegate.as_bytes(),
+        &Delegation {
+            feeder: info.sender.clone(),
+            expires,
+        },
+    )?;
+    FEEDER_DELEGATES.save(
+        deps.storage,
+        (info.sender.as_bytes(), delegate.as_bytes()),
+        &Empty {},
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "authorize_delegate"),
+        attr("feeder", info.sender.to_string()),
+        attr("delegate", delegate.to_string()),
+    ]))
 }
 
-/// Queries the registered feeder for an asset_token
-pub fn query_feeder(deps: Deps, symbol: String) -> Result<FeederResponse, ContractError> {
-    let registered_feeder: Addr =
-        FEEDERS
-            .load(deps.storage, symbol.as_bytes())
+/// Revokes `delegate`, can be called by the feeder that authorized it or the owner
+pub fn revoke_delegate(
+    deps: DepsMut,
+    info: MessageInfo,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let delegate: Addr = deps.api.addr_validate(&delegate)?;
+
+    let delegation: Delegation =
+        DELEGATES
+            .load(deps.storage, delegate.as_bytes())
             .map_err(|_| ContractError::ProxyError {
-                reason: "There is no feeder registered for the provided symbol".to_string(),
+                reason: format!("Delegate {} is not authorized", delegate),
             })?;
 
-    Ok(FeederResponse {
-        symbol,
-        feeder: registered_feeder.to_string(),
-    })
+    if delegation.feeder != info.sender && !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    DELEGATES.remove(deps.storage, delegate.as_bytes());
+    FEEDER_DELEGATES.remove(
+        deps.storage,
+        (delegation.feeder.as_bytes(), delegate.as_bytes()),
+    );
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "revoke_delegate"),
+        attr("feeder", delegation.feeder.to_string()),
+        attr("delegate", delegate.to_string()),
+    ]))
 }
 
-/// Queries last price feed for the symbol
-pub fn query_price(deps: Deps, symbol: String) -> Result<ProxyPriceResponse, ContractError> {
-    let price_info: PriceInfo =
-        PRICES
-            .load(deps.storage, symbol.as_bytes())
-            .map_err(|_| ContractError::ProxyError {
-                reason: "There is no price feed for the requested symbol".to_string(),
-            })?;
+/// Enables, updates or disables the commit-reveal mode of `symbol`.
+/// Any open round and its commits are discarded
+pub fn update_commit_reveal(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    commit_reveal: Option<CommitRevealMsg>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    load_feed(deps.as_ref(), &symbol)?;
+    clear_round(deps.storage, &symbol)?;
+
+    match commit_reveal {
+        Some(msg) => {
+            if msg.commit_period == 0 || msg.reveal_period == 0 {
+                return Err(ContractError::ProxyError {
+                    reason: "Commit and reveal periods must be greater than zero".to_string(),
+                });
+            }
+            COMMIT_REVEAL.save(
+                deps.storage,
+                symbol.as_bytes(),
+                &CommitReveal {
+                    commit_period: msg.commit_period,
+                    reveal_period: msg.reveal_period,
+                },
+            )?;
+        }
+        None => COMMIT_REVEAL.remove(deps.storage, symbol.as_bytes()),
+    }
+
+    Ok(Response::default())
+}
+
+/// Feeder operation to commit price hashes for symbols in commit-reveal mode.
+/// A round that is over is finalized before a new one is opened
+/// ## Parameters
+/// * `commits` - Array of (`symbol`, `hash`)
+pub fn commit_prices(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    commits: Vec<(String, Binary)>,
+) -> Result<Response, ContractError> {
+    let now: u64 = env.block.time.seconds();
+    let delegator: Option<Addr> = load_delegator(deps.storage, &info.sender, now)?;
+
+    let mut attributes: Vec<Attribute> = vec![attr("action", "commit_prices")];
+    for (symbol, hash) in commits {
+        let feed: Feed = load_feed(deps.as_ref(), &symbol)?;
+        let feeder: &Addr = feeder_for(&deps.querier, &feed, &info.sender, &delegator)?;
+        let commit_reveal: CommitReveal = load_commit_reveal(deps.as_ref(), &symbol)?;
+
+        if hash.len() != 32 {
+            return Err(ContractError::ProxyError {
+                reason: "Commit hash must be 32 bytes long".to_string(),
+            });
+        }
+
+        let mut round: Option<Round> = ROUNDS.may_load(deps.storage, symbol.as_bytes())?;
+        if let Some(open) = &round {
+            if now >= open.reveal_end(&commit_reveal) {
+                attributes.extend(close_round(
+                    deps.storage,
+                    &deps.querier,
+                    &symbol,
+                    &feed,
+                    &commit_reveal,
+                    open,
+                )?);
+                round = None;
+            }
+        }
+
+        let round: Round = match round {
+            Some(round) => round,
+            None => {
+                let id: u64 = LAST_ROUNDS
+                    .may_load(deps.storage, symbol.as_bytes())?
+                    .map(|result| result.round_id + 1)
+                    .unwrap_or(1u64);
+                let round = Round {
+                    id,
+                    started_at: now,
+                };
+                ROUNDS.save(deps.storage, symbol.as_bytes(), &round)?;
+                round
+            }
+        };
+
+        if now >= round.commit_end(&commit_reveal) {
+            return Err(ContractError::ProxyError {
+                reason: format!("Round {} of {} is in its reveal phase", round.id, symbol),
+            });
+        }
+
+        // a feeder can replace its commit during the commit phase
+        COMMITS.save(
+            deps.storage,
+            (symbol.as_bytes(), feeder.as_bytes()),
+            &Commit {
+                round_id: round.id,
+                hash,
+                price: None,
+            },
+        )?;
+
+        attributes.push(attr("symbol", symbol));
+        attributes.push(attr("round_id", round.id.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Feeder operation to reveal the prices committed in the open rounds.
+/// Reveals that do not match a commit or violate the guards of their symbol are skipped
+pub fn reveal_prices(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reveals: Vec<RevealMsg>,
+) -> Result<Response, ContractError> {
+    let now: u64 = env.block.time.seconds();
+    let delegator: Option<Addr> = load_delegator(deps.storage, &info.sender, now)?;
+
+    let mut attributes: Vec<Attribute> = vec![attr("action", "reveal_prices")];
+    for reveal in reveals {
+        let feed: Feed = load_feed(deps.as_ref(), &reveal.symbol)?;
+        let feeder: &Addr = feeder_for(&deps.querier, &feed, &info.sender, &delegator)?;
+        let commit_reveal: CommitReveal = load_commit_reveal(deps.as_ref(), &reveal.symbol)?;
+
+        let mut commit: Commit =
+            match load_revealed_commit(deps.storage, &commit_reveal, feeder, &reveal, now)? {
+                Ok(commit) => commit,
+                Err(err) => {
+                    attributes.push(attr("rejected", reveal.symbol));
+                    attributes.push(attr("reason", err.to_string()));
+                    continue;
+                }
+            };
+
+        let guards: FeedGuards = GUARDS
+            .may_load(deps.storage, reveal.symbol.as_bytes())?
+            .unwrap_or_default();
+        let previous: Option<Decimal> = FINALIZED_PRICES
+            .may_load(deps.storage, reveal.symbol.as_bytes())?
+            .map(|finalized| finalized.rate);
+        if let Err(err) = guards.check_reveal(reveal.price, previous) {
+            attributes.push(attr("rejected", reveal.symbol));
+            attributes.push(attr("reason", err.to_string()));
+            continue;
+        }
+
+        commit.price = Some(reveal.price);
+        COMMITS.save(
+            deps.storage,
+            (reveal.symbol.as_bytes(), feeder.as_bytes()),
+            &commit,
+        )?;
+
+        attributes.push(attr("symbol", reveal.symbol.to_string()));
+        attributes.push(attr("price", reveal.price.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Finalizes the open round of `symbol` once its reveal phase is over
+pub fn finalize_round(deps: DepsMut, env: Env, symbol: String) -> Result<Response, ContractError> {
+    let feed: Feed = load_feed(deps.as_ref(), &symbol)?;
+    let commit_reveal: CommitReveal = load_commit_reveal(deps.as_ref(), &symbol)?;
+
+    let round: Round = match ROUNDS.may_load(deps.storage, symbol.as_bytes())? {
+        Some(round) if env.block.time.seconds() >= round.reveal_end(&commit_reveal) => round,
+        _ => {
+            return Err(ContractError::ProxyError {
+                reason: format!("There is no round of {} ready to be finalized", symbol),
+            })
+        }
+    };
+
+    let attributes: Vec<Attribute> = close_round(
+        deps.storage,
+        &deps.querier,
+        &symbol,
+        &feed,
+        &commit_reveal,
+        &round,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "finalize_round")
+        .add_attributes(attributes))
+}
+
+/// Submits price reports signed off-chain by the feeders. Anyone can relay the reports,
+/// reports that fail verification or violate the guards of their symbol are skipped
+pub fn submit_signed_prices(
+    deps: DepsMut,
+    env: Env,
+    reports: Vec<SignedPriceReport>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let now: u64 = env.block.time.seconds();
+
+    let mut attributes: Vec<Attribute> = vec![attr("action", "submit_signed_prices")];
+    for report in reports {
+        let feed: Feed = load_feed(deps.as_ref(), &report.symbol)?;
+        let feeder: Addr = deps.api.addr_validate(&report.feeder)?;
+
+        if let Err(err) = verify_report(deps.as_ref(), &env, &feed, &feeder, &report)? {
+            attributes.push(attr("rejected", report.symbol));
+            attributes.push(attr("reason", err.to_string()));
+            continue;
+        }
+
+        // an expired report can be signed again with the same nonce
+        if let Err(err) = config.check_observation_time(report.timestamp, now) {
+            attributes.push(attr("rejected", report.symbol));
+            attributes.push(attr("reason", err.to_string()));
+            continue;
+        }
+
+        // the nonce is used once the report is verified, even if a guard rejects the price afterwards
+        NONCES.save(deps.storage, feeder.as_bytes(), &report.nonce)?;
+
+        submit_price(
+            deps.storage,
+            &deps.querier,
+            &config,
+            &report.symbol,
+            &feeder,
+            PriceInfo {
+                price: report.price,
+                last_updated_time: report.timestamp,
+                submitted_time: now,
+            },
+            &mut attributes,
+        )?;
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Owner operation to set the submission of every feeder of the symbols to the provided
+/// prices, skipping the guards. The next submissions of the feeders are checked against these prices
+/// ## Parameters
+/// * `prices` - Array of (`symbol`, `price`)
+pub fn force_update_prices(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    prices: Vec<(String, Decimal)>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if !config.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attributes: Vec<Attribute> = vec![attr("action", "force_update_prices")];
+    for (symbol, price) in prices {
+        if price.is_zero() {
+            return Err(ContractError::ProxyError {
+                reason: "Price must be greater than zero".to_string(),
+            });
+        }
+
+        let feed: Feed = load_feed(deps.as_ref(), &symbol)?;
+
+        // symbols in commit-reveal mode serve the forced price until the next round finalizes
+        if COMMIT_REVEAL.has(deps.storage, symbol.as_bytes()) {
+            let round_id: u64 = LAST_ROUNDS
+                .may_load(deps.storage, symbol.as_bytes())?
+                .map(|result| result.round_id)
+                .unwrap_or_default();
+            FINALIZED_PRICES.save(
+                deps.storage,
+                symbol.as_bytes(),
+                &FinalizedPrice {
+                    round_id,
+                    rate: price,
+                    finalized_at: env.block.time.seconds(),
+                },
+            )?;
+            push_observation(
+                deps.storage,
+                &symbol,
+                &Observation {
+                    rate: price,
+                    timestamp: env.block.time.seconds(),
+                },
+            )?;
+
+            attributes.push(attr("symbol", symbol));
+            attributes.push(attr("price", price.to_string()));
+            continue;
+        }
+
+        let feeders: Vec<String> = match &feed.group {
+            Some(group) => load_group_members(&deps.querier, group)?,
+            None => feed.feeders.iter().map(|addr| addr.to_string()).collect(),
+        };
+        for feeder in feeders {
+            SUBMISSIONS.save(
+                deps.storage,
+                (symbol.as_bytes(), feeder.as_bytes()),
+                &PriceInfo {
+                    price,
+                    last_updated_time: env.block.time.seconds(),
+                    submitted_time: env.block.time.seconds(),
+                },
+            )?;
+        }
+        record_price(
+            deps.storage,
+            &deps.querier,
+            &config,
+            &symbol,
+            env.block.time.seconds(),
+        )?;
+
+        attributes.push(attr("symbol", symbol));
+        attributes.push(attr("price", price.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////
+/// Query implementations
+////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Queries the contract configuration
+pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(config.as_res())
+}
+
+/// Queries the registered feeders for an asset_token
+pub fn query_feeder(deps: Deps, symbol: String) -> Result<FeederResponse, ContractError> {
+    let feed: Feed = load_feed(deps, &symbol)?;
+    let ttl: Option<u64> = PRICE_TTLS.may_load(deps.storage, symbol.as_bytes())?;
+
+    Ok(feed.as_res(symbol, ttl))
+}
+
+/// Queries the registered feeds with the latest submission of each feeder
+pub fn query_feeds(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<FeedsResponse, ContractError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(MAX_PAGINATION_LIMIT) as usize;
+    let start = start_after.map(|symbol| Bound::exclusive(symbol.as_bytes()));
+
+    let feeds: Vec<FeedResponse> = FEEDS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, feed) = item?;
+            let symbol = deserialize_key::<String>(k)?;
+            let submissions: Vec<SubmissionResponse> = load_submissions(deps.storage, &symbol)?
+                .into_iter()
+                .map(|(feeder, price_info)| price_info.as_submission_res(feeder))
+                .collect();
+            let ttl: Option<u64> = PRICE_TTLS.may_load(deps.storage, symbol.as_bytes())?;
+
+            Ok(FeedResponse {
+                symbol,
+                feeders: feed.feeders.iter().map(|addr| addr.to_string()).collect(),
+                min_submissions: feed.min_submissions,
+                group: feed.group.map(|group| group.to_string()),
+                weighted: feed.weighted,
+                ttl,
+                submissions,
+            })
+        })
+        .collect::<StdResult<Vec<FeedResponse>>>()?;
+
+    Ok(FeedsResponse { feeds })
+}
+
+/// Queries the symbols the feeder is registered for with its latest submission on each
+pub fn query_feeders_by_address(
+    deps: Deps,
+    feeder: String,
+) -> Result<FeedersByAddressResponse, ContractError> {
+    let feeder_addr: Addr = deps.api.addr_validate(&feeder)?;
+
+    let feeds: Vec<FeederFeedResponse> = FEEDER_FEEDS
+        .prefix(feeder_addr.as_bytes())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|k| {
+            let symbol = deserialize_key::<String>(k)?;
+            let submission: Option<SubmissionResponse> = SUBMISSIONS
+                .may_load(deps.storage, (symbol.as_bytes(), feeder_addr.as_bytes()))?
+                .map(|price_info| price_info.as_submission_res(feeder.clone()));
+
+            Ok(FeederFeedResponse { symbol, submission })
+        })
+        .collect::<StdResult<Vec<FeederFeedResponse>>>()?;
+
+    Ok(FeedersByAddressResponse { feeder, feeds })
+}
+
+/// Queries the latest submission of each feeder of the symbol
+pub fn query_submissions(deps: Deps, symbol: String) -> Result<SubmissionsResponse, ContractError> {
+    let submissions: Vec<SubmissionResponse> = load_submissions(deps.storage, &symbol)?
+        .into_iter()
+        .map(|(feeder, price_info)| price_info.as_submission_res(feeder))
+        .collect();
+
+    Ok(SubmissionsResponse {
+        symbol,
+        submissions,
+    })
+}
+
+/// Queries the guards of the symbol
+pub fn query_guards(deps: Deps, symbol: String) -> Result<GuardsResponse, ContractError> {
+    load_feed(deps, &symbol)?;
+    let guards: FeedGuards = GUARDS
+        .may_load(deps.storage, symbol.as_bytes())?
+        .unwrap_or_default();
+
+    Ok(guards.as_res(symbol))
+}
+
+/// Queries the public key and the last used nonce of a feeder
+pub fn query_feeder_key(deps: Deps, feeder: String) -> Result<FeederKeyResponse, ContractError> {
+    let feeder_addr: Addr = deps.api.addr_validate(&feeder)?;
+
+    Ok(FeederKeyResponse {
+        feeder,
+        public_key: FEEDER_KEYS.may_load(deps.storage, feeder_addr.as_bytes())?,
+        last_nonce: NONCES.may_load(deps.storage, feeder_addr.as_bytes())?,
+    })
+}
+
+/// Queries the commit-reveal configuration, the open round and the last closed round of the symbol
+pub fn query_round(deps: Deps, symbol: String) -> Result<RoundResponse, ContractError> {
+    load_feed(deps, &symbol)?;
+    let commit_reveal: CommitReveal = load_commit_reveal(deps, &symbol)?;
+
+    let current_round: Option<CurrentRoundResponse> =
+        match ROUNDS.may_load(deps.storage, symbol.as_bytes())? {
+            Some(round) => {
+                let commits: Vec<(String, Commit)> = load_commits(deps.storage, &symbol)?;
+                Some(CurrentRoundResponse {
+                    round_id: round.id,
+                    started_at: round.started_at,
+                    commit_end: round.commit_end(&commit_reveal),
+                    reveal_end: round.reveal_end(&commit_reveal),
+                    committed: commits.iter().map(|(feeder, _)| feeder.clone()).collect(),
+                    revealed: commits
+                        .into_iter()
+                        .filter(|(_, commit)| commit.price.is_some())
+                        .map(|(feeder, _)| feeder)
+                        .collect(),
+                })
+            }
+            None => None,
+        };
+
+    let last_round: Option<RoundResultResponse> = LAST_ROUNDS
+        .may_load(deps.storage, symbol.as_bytes())?
+        .map(|result| result.as_res());
+
+    Ok(RoundResponse {
+        symbol,
+        commit_period: commit_reveal.commit_period,
+        reveal_period: commit_reveal.reveal_period,
+        current_round,
+        last_round,
+    })
+}
+
+/// Queries the delegates authorized by a feeder
+pub fn query_delegates(deps: Deps, feeder: String) -> Result<DelegatesResponse, ContractError> {
+    let feeder_addr: Addr = deps.api.addr_validate(&feeder)?;
+
+    let delegates: Vec<DelegateResponse> = FEEDER_DELEGATES
+        .prefix(feeder_addr.as_bytes())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|k| {
+            let delegate = deserialize_key::<String>(k)?;
+            let delegation: Delegation = DELEGATES.load(deps.storage, delegate.as_bytes())?;
+
+            Ok(DelegateResponse {
+                delegate,
+                expires: delegation.expires,
+            })
+        })
+        .collect::<StdResult<Vec<DelegateResponse>>>()?;
+
+    Ok(DelegatesResponse { feeder, delegates })
+}
+
+/// Queries the latest submission time of every feeder and the symbols it is overdue on,
+/// which are the symbols with a ttl on which the feeder has no live submission.
+/// Symbols in commit-reveal mode report their non revealers in the round query instead
+pub fn query_liveness(deps: Deps, env: Env) -> Result<LivenessResponse, ContractError> {
+    let now: u64 = env.block.time.seconds();
+
+    // (last_submitted, overdue) of each feeder, sorted by address
+    let mut liveness: BTreeMap<String, (Option<u64>, Vec<String>)> = BTreeMap::new();
+    for item in FEEDS.range(deps.storage, None, None, Order::Ascending) {
+        let (k, feed) = item?;
+        let symbol = deserialize_key::<String>(k)?;
+        let ttl: Option<u64> = PRICE_TTLS.may_load(deps.storage, symbol.as_bytes())?;
+        let commit_reveal: bool = COMMIT_REVEAL.has(deps.storage, symbol.as_bytes());
+
+        let feeders: Vec<String> = match &feed.group {
+            Some(group) => load_group_members(&deps.querier, group)?,
+            None => feed.feeders.iter().map(|addr| addr.to_string()).collect(),
+        };
+        for feeder in feeders {
+            let submission: Option<PriceInfo> =
+                SUBMISSIONS.may_load(deps.storage, (symbol.as_bytes(), feeder.as_bytes()))?;
+            let (last_submitted, overdue) = liveness.entry(feeder).or_default();
+
+            if let Some(submission) = &submission {
+                *last_submitted = (*last_submitted).max(Some(submission.submitted_time));
+            }
+
+            let live: bool = submission
+                .map(|submission| !is_expired(ttl, submission.last_updated_time, now))
+                .unwrap_or(false);
+            if ttl.is_some() && !commit_reveal && !live {
+                overdue.push(symbol.clone());
+            }
+        }
+    }
+
+    Ok(LivenessResponse {
+        feeders: liveness
+            .into_iter()
+            .map(
+                |(feeder, (last_submitted, overdue))| FeederLivenessResponse {
+                    feeder,
+                    last_submitted,
+                    overdue,
+                },
+            )
+            .collect(),
+    })
+}
+
+/// Queries the median of the fresh submissions for the symbol, reported with the
+/// observation time of the oldest submission used
+pub fn query_price(
+    deps: Deps,
+    env: Env,
+    symbol: String,
+) -> Result<ProxyPriceResponse, ContractError> {
+    let res: PriceDetailResponse = query_price_detail(deps, env, symbol)?;
+
+    Ok(ProxyPriceResponse {
+        rate: res.rate,
+        last_updated: res.last_updated,
+    })
+}
+
+/// Queries the median of the fresh submissions for the symbol, reported with the
+/// observation and submission times of the oldest submission used
+pub fn query_price_detail(
+    deps: Deps,
+    env: Env,
+    symbol: String,
+) -> Result<PriceDetailResponse, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let feed: Feed = load_feed(deps, &symbol)?;
+
+    if let Some(commit_reveal) = COMMIT_REVEAL.may_load(deps.storage, symbol.as_bytes())? {
+        return finalized_price(
+            deps.storage,
+            &deps.querier,
+            &config,
+            &feed,
+            &commit_reveal,
+            symbol,
+            env.block.time.seconds(),
+        );
+    }
+
+    aggregate_price(
+        deps.storage,
+        &deps.querier,
+        &config,
+        &feed,
+        symbol,
+        env.block.time.seconds(),
+    )
+}
+
+/// Queries the recorded prices of the symbol from oldest to newest
+pub fn query_history(
+    deps: Deps,
+    symbol: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<HistoryResponse, ContractError> {
+    load_feed(deps, &symbol)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(MAX_PAGINATION_LIMIT) as usize;
+
+    let observations: Vec<ObservationResponse> = load_observations(deps.storage, &symbol)?
+        .into_iter()
+        .filter(|observation| match start_after {
+            Some(start_after) => observation.timestamp > start_after,
+            None => true,
+        })
+        .take(limit)
+        .map(|observation| ObservationResponse {
+            rate: observation.rate,
+            timestamp: observation.timestamp,
+        })
+        .collect();
+
+    Ok(HistoryResponse {
+        symbol,
+        observations,
+    })
+}
+
+/// Queries the time weighted average price of `symbol` over the last `window` seconds.
+/// The latest observation is not extended past the ttl of the symbol
+pub fn query_twap(
+    deps: Deps,
+    env: Env,
+    symbol: String,
+    window: u64,
+) -> Result<TwapResponse, ContractError> {
+    load_feed(deps, &symbol)?;
+    let observations: Vec<Observation> = load_observations(deps.storage, &symbol)?;
+    let last_updated = match observations.last() {
+        Some(latest) => latest.timestamp,
+        None => return Err(ContractError::HistoryNotAvailable {}),
+    };
+
+    let end = env.block.time.seconds();
+    let start = end
+        .checked_sub(window)
+        .ok_or(ContractError::HistoryNotAvailable {})?;
+    let ttl: Option<u64> = PRICE_TTLS.may_load(deps.storage, symbol.as_bytes())?;
+    let rate = time_weighted_average(&observations, start, end, ttl)?;
+
+    Ok(TwapResponse {
+        symbol,
+        rate: rate.into(),
+        window,
+        last_updated,
+    })
+}
+
+// Helper functions
+
+/// Returns the median of the fresh submissions of `symbol` at `now`. Expired submissions and
+/// the submissions of addresses that left the group of a group feed are ignored
+fn aggregate_price(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    feed: &Feed,
+    symbol: String,
+    now: u64,
+) -> Result<PriceDetailResponse, ContractError> {
+    let ttl: Option<u64> = PRICE_TTLS.may_load(storage, symbol.as_bytes())?;
+
+    let mut fresh: Vec<PriceInfo> = vec![];
+    let mut weighted_prices: Vec<(Decimal, u64)> = vec![];
+    for (feeder, price_info) in load_submissions(storage, &symbol)? {
+        if !config.is_fresh(price_info.last_updated_time, now)
+            || is_expired(ttl, price_info.last_updated_time, now)
+        {
+            continue;
+        }
+        if let Some(weight) = feeder_weight(querier, feed, &Addr::unchecked(feeder))? {
+            weighted_prices.push((price_info.price, weight));
+            fresh.push(price_info);
+        }
+    }
+
+    if fresh.len() < feed.min_submissions as usize {
+        return Err(ContractError::ProxyError {
+            reason: format!(
+                "Not enough fresh submissions for the requested symbol ({} of {})",
+                fresh.len(),
+                feed.min_submissions
+            ),
+        });
+    }
+
+    let rate: Decimal =
+        feed_median(feed, weighted_prices).ok_or_else(|| ContractError::ProxyError {
+            reason: "There is no price feed for the requested symbol".to_string(),
+        })?;
+    let last_updated: u64 = fresh
+        .iter()
+        .map(|price_info| price_info.last_updated_time)
+        .min()
+        .unwrap_or_default();
+    let last_submitted: u64 = fresh
+        .iter()
+        .map(|price_info| price_info.submitted_time)
+        .min()
+        .unwrap_or_default();
+
+    Ok(PriceDetailResponse {
+        symbol,
+        rate,
+        last_updated,
+        last_submitted,
+    })
+}
+
+/// Records the current price of `symbol` in its history, nothing is recorded
+/// while there are not enough fresh submissions
+fn record_price(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    symbol: &str,
+    now: u64,
+) -> Result<(), ContractError> {
+    let feed: Feed = FEEDS.load(storage, symbol.as_bytes())?;
+
+    match aggregate_price(storage, querier, config, &feed, symbol.to_string(), now) {
+        Ok(res) => push_observation(
+            storage,
+            symbol,
+            &Observation {
+                rate: res.rate,
+                timestamp: now,
+            },
+        )?,
+        Err(ContractError::ProxyError { .. }) => {}
+        Err(err) => return Err(err),
+    }
+
+    Ok(())
+}
+
+/// Checks if a price observed at `timestamp` is older than `ttl` seconds at `now`
+fn is_expired(ttl: Option<u64>, timestamp: u64, now: u64) -> bool {
+    match ttl {
+        Some(ttl) => timestamp.saturating_add(ttl) < now,
+        None => false,
+    }
+}
+
+/// Loads the feed registered for `symbol`
+fn load_feed(deps: Deps, symbol: &str) -> Result<Feed, ContractError> {
+    FEEDS
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::ProxyError {
+            reason: "There is no feeder registered for the provided symbol".to_string(),
+        })
+}
+
+/// Loads the latest submission of each feeder of `symbol` as (feeder, submission)
+fn load_submissions(storage: &dyn Storage, symbol: &str) -> StdResult<Vec<(String, PriceInfo)>> {
+    SUBMISSIONS
+        .prefix(symbol.as_bytes())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, price_info) = item?;
+            Ok((deserialize_key::<String>(k)?, price_info))
+        })
+        .collect()
+}
+
+/// Checks the observation time of `submission` and the guards of `symbol` and saves it as the
+/// submission of `feeder`, a rejected price is reported in the `rejected` and `reason` attributes instead
+fn submit_price(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    symbol: &str,
+    feeder: &Addr,
+    submission: PriceInfo,
+    attributes: &mut Vec<Attribute>,
+) -> Result<(), ContractError> {
+    let guards: FeedGuards = GUARDS
+        .may_load(storage, symbol.as_bytes())?
+        .unwrap_or_default();
+    let previous: Option<PriceInfo> =
+        SUBMISSIONS.may_load(storage, (symbol.as_bytes(), feeder.as_bytes()))?;
+
+    let commit_reveal_only: Result<(), SubmissionError> =
+        match COMMIT_REVEAL.has(storage, symbol.as_bytes()) {
+            true => Err(SubmissionError::CommitRevealOnly {}),
+            false => Ok(()),
+        };
+
+    if let Err(err) = commit_reveal_only
+        .and_then(|_| {
+            config.check_observation_time(submission.last_updated_time, submission.submitted_time)
+        })
+        .and_then(|_| guards.check(&submission, previous.as_ref()))
+    {
+        attributes.push(attr("rejected", symbol));
+        attributes.push(attr("reason", err.to_string()));
+        return Ok(());
+    }
+
+    attributes.push(attr("symbol", symbol));
+    attributes.push(attr("price", submission.price.to_string()));
+
+    SUBMISSIONS.save(storage, (symbol.as_bytes(), feeder.as_bytes()), &submission)?;
+
+    record_price(storage, querier, config, symbol, submission.submitted_time)
+}
+
+/// Verifies the feeder, nonce and signature of a signed report.
+/// The outer result fails on storage errors, the inner one holds the rejection reason
+fn verify_report(
+    deps: Deps,
+    env: &Env,
+    feed: &Feed,
+    feeder: &Addr,
+    report: &SignedPriceReport,
+) -> StdResult<Result<(), ReportError>> {
+    if feeder_weight(&deps.querier, feed, feeder)?.is_none() {
+        return Ok(Err(ReportError::NotFeeder {
+            feeder: feeder.to_string(),
+        }));
+    }
+
+    if let Some(last_nonce) = NONCES.may_load(deps.storage, feeder.as_bytes())? {
+        if report.nonce <= last_nonce {
+            return Ok(Err(ReportError::NonceUsed {
+                nonce: report.nonce,
+                last_nonce,
+            }));
+        }
+    }
+
+    let public_key: Binary = match FEEDER_KEYS.may_load(deps.storage, feeder.as_bytes())? {
+        Some(public_key) => public_key,
+        None => {
+            return Ok(Err(ReportError::MissingPublicKey {
+                feeder: feeder.to_string(),
+            }))
+        }
+    };
+
+    let hash: Vec<u8> = report_hash(&PriceReport {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        feeder: feeder.to_string(),
+        symbol: report.symbol.clone(),
+        price: report.price,
+        timestamp: report.timestamp,
+        nonce: report.nonce,
+    })?;
+
+    // malformed signatures are treated as invalid ones
+    let verified: bool = deps
+        .api
+        .secp256k1_verify(&hash, &report.signature, &public_key)
+        .unwrap_or(false);
+    if !verified {
+        return Ok(Err(ReportError::InvalidSignature {}));
+    }
+
+    Ok(Ok(()))
+}
+
+/// Returns the sha256 hash of the JSON encoded `report`, which is the message signed by the feeders
+pub fn report_hash(report: &PriceReport) -> StdResult<Vec<u8>> {
+    Ok(Sha256::digest(&to_vec(report)?).to_vec())
+}
+
+/// Checks that `public_key` is a compressed (33 bytes) or uncompressed (65 bytes)
+/// secp256k1 public key, starting with the matching prefix byte
+fn is_valid_public_key(public_key: &[u8]) -> bool {
+    matches!(
+        (public_key.len(), public_key.first()),
+        (33, Some(0x02)) | (33, Some(0x03)) | (65, Some(0x04))
+    )
+}
+
+/// Returns the feeder `public_key` is registered for, if any
+fn key_feeder(storage: &dyn Storage, public_key: &Binary) -> StdResult<Option<String>> {
+    for item in FEEDER_KEYS.range(storage, None, None, Order::Ascending) {
+        let (k, key) = item?;
+        if key == *public_key {
+            return Ok(Some(deserialize_key::<String>(k)?));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Validates and saves the `feeders` of `symbol`, removing the submissions of
+/// feeders that are no longer registered, including the members of a previous group
+fn save_feed(
+    deps: DepsMut,
+    symbol: &str,
+    feeders: Vec<String>,
+    min_submissions: u32,
+) -> Result<(), ContractError> {
+    let mut feeder_addrs: Vec<Addr> = vec![];
+    for feeder in feeders {
+        let feeder: Addr = deps.api.addr_validate(&feeder)?;
+        if feeder_addrs.contains(&feeder) {
+            return Err(ContractError::ProxyError {
+                reason: format!("Feeder {} is provided more than once", feeder),
+            });
+        }
+        feeder_addrs.push(feeder);
+    }
+
+    if min_submissions == 0 || min_submissions as usize > feeder_addrs.len() {
+        return Err(ContractError::ProxyError {
+            reason: "Minimum submissions must be between 1 and the number of feeders".to_string(),
+        });
+    }
+
+    // remove the submissions of feeders that are no longer registered
+    if let Some(previous) = FEEDS.may_load(deps.storage, symbol.as_bytes())? {
+        for feeder in previous.feeders {
+            if !feeder_addrs.contains(&feeder) {
+                FEEDER_FEEDS.remove(deps.storage, (feeder.as_bytes(), symbol.as_bytes()));
+            }
+        }
+    }
+    for (feeder, _) in load_submissions(deps.storage, symbol)? {
+        if !feeder_addrs.contains(&Addr::unchecked(&feeder)) {
+            SUBMISSIONS.remove(deps.storage, (symbol.as_bytes(), feeder.as_bytes()));
+        }
+    }
+
+    for feeder in feeder_addrs.iter() {
+        FEEDER_FEEDS.save(
+            deps.storage,
+            (feeder.as_bytes(), symbol.as_bytes()),
+            &Empty {},
+        )?;
+    }
+
+    // overwrite if exists
+    FEEDS.save(
+        deps.storage,
+        symbol.as_bytes(),
+        &Feed {
+            feeders: feeder_addrs,
+            min_submissions,
+            group: None,
+            weighted: false,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Returns the price of the last finalized round of `symbol`, including a round whose
+/// reveal phase is over but that has not been finalized yet
+fn finalized_price(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    feed: &Feed,
+    commit_reveal: &CommitReveal,
+    symbol: String,
+    now: u64,
+) -> Result<PriceDetailResponse, ContractError> {
+    let mut finalized: Option<FinalizedPrice> =
+        FINALIZED_PRICES.may_load(storage, symbol.as_bytes())?;
+
+    if let Some(round) = ROUNDS.may_load(storage, symbol.as_bytes())? {
+        if now >= round.reveal_end(commit_reveal) {
+            let result: RoundResult =
+                round_result(storage, querier, &symbol, feed, commit_reveal, &round)?;
+            if let (Some(rate), true) = (result.rate, is_newer(&finalized, result.ended_at)) {
+                finalized = Some(FinalizedPrice {
+                    round_id: result.round_id,
+                    rate,
+                    finalized_at: result.ended_at,
+                });
+            }
+        }
+    }
+
+    let ttl: Option<u64> = PRICE_TTLS.may_load(storage, symbol.as_bytes())?;
+
+    match finalized {
+        Some(finalized)
+            if config.is_fresh(finalized.finalized_at, now)
+                && !is_expired(ttl, finalized.finalized_at, now) =>
+        {
+            Ok(PriceDetailResponse {
+                symbol,
+                rate: finalized.rate,
+                last_updated: finalized.finalized_at,
+                last_submitted: finalized.finalized_at,
+            })
+        }
+        Some(_) => Err(ContractError::ProxyError {
+            reason: "The last finalized round of the requested symbol is too old".to_string(),
+        }),
+        None => Err(ContractError::ProxyError {
+            reason: "There is no finalized round for the requested symbol".to_string(),
+        }),
+    }
+}
+
+/// Computes the outcome of `round` from its commits, the round finalizes to the
+/// median of the reveals if there are at least `min_submissions` of them. The reveals
+/// of addresses that left the group of a group feed are ignored
+fn round_result(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper,
+    symbol: &str,
+    feed: &Feed,
+    commit_reveal: &CommitReveal,
+    round: &Round,
+) -> StdResult<RoundResult> {
+    let mut prices: Vec<(Decimal, u64)> = vec![];
+    let mut revealed: Vec<Addr> = vec![];
+    let mut non_revealers: Vec<Addr> = vec![];
+    for (feeder, commit) in load_commits(storage, symbol)? {
+        if commit.round_id != round.id {
+            continue;
+        }
+        let feeder = Addr::unchecked(feeder);
+        match commit.price {
+            Some(price) => {
+                if let Some(weight) = feeder_weight(querier, feed, &feeder)? {
+                    prices.push((price, weight));
+                }
+                revealed.push(feeder);
+            }
+            None => non_revealers.push(feeder),
+        }
+    }
+
+    let rate: Option<Decimal> = match prices.len() >= feed.min_submissions as usize {
+        true => feed_median(feed, prices),
+        false => None,
+    };
+
+    Ok(RoundResult {
+        round_id: round.id,
+        rate,
+        ended_at: round.reveal_end(commit_reveal),
+        revealed,
+        non_revealers,
+    })
+}
+
+/// Closes `round`, recording its outcome and the price it finalized to, if any.
+/// A price forced by the owner after the end of the round is kept
+fn close_round(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    symbol: &str,
+    feed: &Feed,
+    commit_reveal: &CommitReveal,
+    round: &Round,
+) -> StdResult<Vec<Attribute>> {
+    let result: RoundResult = round_result(storage, querier, symbol, feed, commit_reveal, round)?;
+    let previous: Option<FinalizedPrice> = FINALIZED_PRICES.may_load(storage, symbol.as_bytes())?;
+
+    let mut attributes: Vec<Attribute> = vec![
+        attr("finalized_symbol", symbol),
+        attr("finalized_round", round.id.to_string()),
+    ];
+    match result.rate {
+        Some(rate) if is_newer(&previous, result.ended_at) => {
+            FINALIZED_PRICES.save(
+                storage,
+                symbol.as_bytes(),
+                &FinalizedPrice {
+                    round_id: round.id,
+                    rate,
+                    finalized_at: result.ended_at,
+                },
+            )?;
+            push_observation(
+                storage,
+                symbol,
+                &Observation {
+                    rate,
+                    timestamp: result.ended_at,
+                },
+            )?;
+            attributes.push(attr("finalized_price", rate.to_string()));
+        }
+        _ => attributes.push(attr("finalized_price", "none")),
+    }
+    for feeder in result.non_revealers.iter() {
+        attributes.push(attr("non_revealer", feeder.to_string()));
+    }
+
+    LAST_ROUNDS.save(storage, symbol.as_bytes(), &result)?;
+    clear_round(storage, symbol)?;
+
+    Ok(attributes)
+}
+
+/// Loads the commit of `feeder` that `reveal` opens, which must be part of the round
+/// of its symbol in its reveal phase
+fn load_revealed_commit(
+    storage: &dyn Storage,
+    commit_reveal: &CommitReveal,
+    feeder: &Addr,
+    reveal: &RevealMsg,
+    now: u64,
+) -> StdResult<Result<Commit, RevealError>> {
+    let symbol: &str = &reveal.symbol;
+    let round: Round = match ROUNDS.may_load(storage, symbol.as_bytes())? {
+        Some(round)
+            if now >= round.commit_end(commit_reveal) && now < round.reveal_end(commit_reveal) =>
+        {
+            round
+        }
+        _ => {
+            return Ok(Err(RevealError::NotRevealPhase {
+                symbol: symbol.to_string(),
+            }))
+        }
+    };
+
+    let commit: Commit = match COMMITS.may_load(storage, (symbol.as_bytes(), feeder.as_bytes()))? {
+        Some(commit) if commit.round_id == round.id && commit.price.is_none() => commit,
+        _ => {
+            return Ok(Err(RevealError::NoCommit {
+                symbol: symbol.to_string(),
+            }))
+        }
+    };
+
+    let hash: Vec<u8> = commit_hash(
+        feeder.as_str(),
+        symbol,
+        round.id,
+        reveal.price,
+        &reveal.salt,
+    );
+    if commit.hash.as_slice() != hash.as_slice() {
+        return Ok(Err(RevealError::HashMismatch {
+            symbol: symbol.to_string(),
+        }));
+    }
+
+    Ok(Ok(commit))
+}
+
+/// Checks if a round that ended at `ended_at` is newer than the `previous` finalized price
+fn is_newer(previous: &Option<FinalizedPrice>, ended_at: u64) -> bool {
+    match previous {
+        Some(previous) => ended_at > previous.finalized_at,
+        None => true,
+    }
+}
+
+/// Removes the open round of `symbol` with its commits
+fn clear_round(storage: &mut dyn Storage, symbol: &str) -> StdResult<()> {
+    for (feeder, _) in load_commits(storage, symbol)? {
+        COMMITS.remove(storage, (symbol.as_bytes(), feeder.as_bytes()));
+    }
+    ROUNDS.remove(storage, symbol.as_bytes());
+
+    Ok(())
+}
+
+/// Loads the commits of `symbol` as (feeder, commit)
+fn load_commits(storage: &dyn Storage, symbol: &str) -> StdResult<Vec<(String, Commit)>> {
+    COMMITS
+        .prefix(symbol.as_bytes())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, commit) = item?;
+            Ok((deserialize_key::<String>(k)?, commit))
+        })
+        .collect()
+}
+
+/// Loads the commit-reveal periods of `symbol`
+fn load_commit_reveal(deps: Deps, symbol: &str) -> Result<CommitReveal, ContractError> {
+    COMMIT_REVEAL
+        .load(deps.storage, symbol.as_bytes())
+        .map_err(|_| ContractError::ProxyError {
+            reason: format!("Symbol {} is not in commit-reveal mode", symbol),
+        })
+}
+
+/// Loads the feeder `sender` submits for when acting as an active delegate
+fn load_delegator(storage: &dyn Storage, sender: &Addr, now: u64) -> StdResult<Option<Addr>> {
+    Ok(DELEGATES
+        .may_load(storage, sender.as_bytes())?
+        .filter(|delegation| delegation.is_active(now))
+        .map(|delegation| delegation.feeder))
+}
+
+/// Returns the feeder of `feed` the `sender` submits for, either itself or its `delegator`
+fn feeder_for<'a>(
+    querier: &QuerierWrapper,
+    feed: &Feed,
+    sender: &'a Addr,
+    delegator: &'a Option<Addr>,
+) -> Result<&'a Addr, ContractError> {
+    if feeder_weight(querier, feed, sender)?.is_some() {
+        return Ok(sender);
+    }
+
+    match delegator {
+        Some(delegator) if feeder_weight(querier, feed, delegator)?.is_some() => Ok(delegator),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Returns the weight of `addr` in `feed`, `None` if it is not a feeder. Listed feeders
+/// weigh 1, the members of a group feed have their membership weight, members without
+/// weight are not feeders
+fn feeder_weight(querier: &QuerierWrapper, feed: &Feed, addr: &Addr) -> StdResult<Option<u64>> {
+    match &feed.group {
+        Some(group) => {
+            let res: MemberResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: group.to_string(),
+                msg: to_binary(&Cw4QueryMsg::Member {
+                    addr: addr.to_string(),
+                    at_height: None,
+                })?,
+            }))?;
+            Ok(res.weight.filter(|weight| *weight > 0))
+        }
+        None => Ok(match feed.is_feeder(addr) {
+            true => Some(1u64),
+            false => None,
+        }),
+    }
+}
+
+/// Checks if `addr` is a feeder of any group feed, each group is queried once
+fn is_group_feeder(deps: Deps, addr: &Addr) -> StdResult<bool> {
+    let mut groups: Vec<Addr> = vec![];
+    for item in FEEDS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, feed) = item?;
+        let group: &Addr = match &feed.group {
+            Some(group) if !groups.contains(group) => group,
+            _ => continue,
+        };
+        if feeder_weight(&deps.querier, &feed, addr)?.is_some() {
+            return Ok(true);
+        }
+        groups.push(group.clone());
+    }
+
+    Ok(false)
+}
+
+/// Returns the median of (`price`, `weight`) pairs, weighted for weighted group feeds
+fn feed_median(feed: &Feed, mut prices: Vec<(Decimal, u64)>) -> Option<Decimal> {
+    if feed.weighted {
+        return weighted_median(&mut prices);
+    }
+
+    let mut prices: Vec<Decimal> = prices.into_iter().map(|(price, _)| price).collect();
+    median(&mut prices)
+}
+
+/// Loads the addresses of all the members of the cw4 `group` with a weight
+fn load_group_members(querier: &QuerierWrapper, group: &Addr) -> StdResult<Vec<String>> {
+    let mut members: Vec<String> = vec![];
+    let mut start_after: Option<String> = None;
+    loop {
+        let res: MemberListResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: group.to_string(),
+            msg: to_binary(&Cw4QueryMsg::ListMembers {
+                start_after: start_after.clone(),
+                limit: Some(MAX_PAGINATION_LIMIT),
+            })?,
+        }))?;
+        if res.members.is_empty() {
+            return Ok(members);
+        }
+        start_after = res.members.last().map(|member| member.addr.clone());
+        members.extend(
+            res.members
+                .into_iter()
+                .filter(|member| member.weight > 0)
+                .map(|member| member.addr),
+        );
+    }
+}
 
-    Ok(price_info.as_res())
+/// Returns the sha256 hash of "{feeder}:{symbol}:{round_id}:{price}:{salt}", which is the hash
+/// committed by the feeders. Binding it to the feeder and round keeps commits from being replayed
+pub fn commit_hash(
+    feeder: &str,
+    symbol: &str,
+    round_id: u64,
+    price: Decimal,
+    salt: &str,
+) -> Vec<u8> {
+    Sha256::digest(format!("{}:{}:{}:{}:{}", feeder, symbol, round_id, price, salt).as_bytes())
+        .to_vec()
 }
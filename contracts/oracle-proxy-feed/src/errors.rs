@@ -0,0 +1,71 @@
+use cosmwasm_std::Decimal;
+use thiserror::Error;
+
+/// Reasons why a price submission is rejected by the guards of its symbol
+#[derive(Error, Debug, PartialEq)]
+pub enum SubmissionError {
+    #[error("Price must be greater than zero")]
+    ZeroPrice {},
+
+    #[error("Prices of this symbol must be submitted with commit-reveal")]
+    CommitRevealOnly {},
+
+    #[error("Observation timestamp {timestamp} is in the future")]
+    FutureTimestamp { timestamp: u64 },
+
+    #[error("Observation timestamp {timestamp} is older than {max_observation_age} seconds")]
+    TooOld {
+        timestamp: u64,
+        max_observation_age: u64,
+    },
+
+    #[error("Observation timestamp {timestamp} is older than the previous one {previous}")]
+    OutOfOrder { timestamp: u64, previous: u64 },
+
+    #[error("Price was already updated in the last {min_update_interval} seconds")]
+    TooFrequent { min_update_interval: u64 },
+
+    #[error(
+        "Price {price} moves more than {max_change_bps} bps from the previous price {previous}"
+    )]
+    MaxMoveExceeded {
+        previous: Decimal,
+        price: Decimal,
+        max_change_bps: u64,
+    },
+
+    #[error("Price {price} is below the minimum of {min_price}")]
+    BelowMinimum { price: Decimal, min_price: Decimal },
+
+    #[error("Price {price} is above the maximum of {max_price}")]
+    AboveMaximum { price: Decimal, max_price: Decimal },
+}
+
+/// Reasons why a revealed price is rejected
+#[derive(Error, Debug, PartialEq)]
+pub enum RevealError {
+    #[error("There is no round of {symbol} in its reveal phase")]
+    NotRevealPhase { symbol: String },
+
+    #[error("There is no commit to reveal for {symbol}")]
+    NoCommit { symbol: String },
+
+    #[error("Revealed price does not match the commit for {symbol}")]
+    HashMismatch { symbol: String },
+}
+
+/// Reasons why a signed price report is rejected
+#[derive(Error, Debug, PartialEq)]
+pub enum ReportError {
+    #[error("Feeder {feeder} has no registered public key")]
+    MissingPublicKey { feeder: String },
+
+    #[error("Feeder {feeder} is not registered for the symbol")]
+    NotFeeder { feeder: String },
+
+    #[error("Invalid report signature")]
+    InvalidSignature {},
+
+    #[error("Report nonce {nonce} is not greater than the last used nonce {last_nonce}")]
+    NonceUsed { nonce: u64, last_nonce: u64 },
+}
@@ -1,5 +1,10 @@
 pub mod contract;
+pub mod errors;
+pub mod math;
 pub mod msg;
 pub mod state;
 
+#[cfg(test)]
+mod testing;
+
 pub use tefi_oracle::errors::ContractError;
@@ -0,0 +1,63 @@
+use cosmwasm_std::{Decimal, Fraction, Uint128};
+
+/// Returns the median of `prices`, the average of the two middle prices for an even count.
+/// Returns `None` if `prices` is empty
+pub fn median(prices: &mut [Decimal]) -> Option<Decimal> {
+    if prices.is_empty() {
+        return None;
+    }
+
+    prices.sort();
+    let middle = prices.len() / 2;
+    if prices.len() % 2 == 1 {
+        return Some(prices[middle]);
+    }
+
+    // halve the difference so the sum of both prices can not overflow
+    let (low, high) = (prices[middle - 1], prices[middle]);
+    Some(low + (high - low) / Uint128::new(2u128))
+}
+
+/// Returns the weighted median of (`price`, `weight`) pairs, the lowest price at which the
+/// cumulative weight reaches half of the total weight. When it is reached exactly, the average
+/// with the next price is used so that equal weights give the same result as [`median`].
+/// Returns `None` if the total weight is zero
+pub fn weighted_median(prices: &mut [(Decimal, u64)]) -> Option<Decimal> {
+    let total: u128 = prices.iter().map(|(_, weight)| *weight as u128).sum();
+    if total == 0 {
+        return None;
+    }
+
+    prices.sort_by_key(|(price, _)| *price);
+    let mut cumulative: u128 = 0;
+    for (i, (price, weight)) in prices.iter().enumerate() {
+        cumulative += *weight as u128;
+        if cumulative * 2 < total {
+            continue;
+        }
+
+        if cumulative * 2 == total {
+            // skip zero weights so the average uses the next price that carries weight
+            if let Some((next, _)) = prices[i + 1..].iter().find(|(_, weight)| *weight > 0) {
+                return Some(*price + (*next - *price) / Uint128::new(2u128));
+            }
+        }
+
+        return Some(*price);
+    }
+
+    None
+}
+
+/// Checks if moving from `previous` to `price` changes the price by more than `max_change_bps` basis points
+pub fn exceeds_max_change(previous: Decimal, price: Decimal, max_change_bps: u64) -> bool {
+    let delta: Decimal = if price > previous {
+        price - previous
+    } else {
+        previous - price
+    };
+
+    // delta / previous > max_change_bps / 10_000, compared on the atomics to avoid rounding
+    Uint128::new(delta.numerator()).full_mul(10_000u64)
+        > Uint128::new(previous.numerator()).full_mul(max_change_bps)
+}
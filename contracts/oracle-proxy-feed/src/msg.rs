@@ -1,4 +1,4 @@
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Binary, Decimal};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tefi_oracle::proxy::ProxyQueryMsg;
@@ -6,21 +6,123 @@ use tefi_oracle::proxy::ProxyQueryMsg;
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: String,
+    pub submission_window: Option<u64>,
+    pub max_observation_age: Option<u64>,
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     UpdateOwner {
         owner: String,
     },
-    /// Used to register new asset or to update feeder
+    /// Sets the number of seconds a submission is used for, `None` uses
+    /// the latest submission of every feeder regardless of its age
+    UpdateSubmissionWindow {
+        submission_window: Option<u64>,
+    },
+    /// Sets the maximum age in seconds of an observation timestamp when the price is
+    /// submitted, `None` accepts observations of any age
+    UpdateMaxObservationAge {
+        max_observation_age: Option<u64>,
+    },
+    /// Sets the secp256k1 public keys used to verify the signed reports of
+    /// each feeder, `None` removes the key of the feeder
+    SetFeederKeys {
+        keys: Vec<(String, Option<Binary>)>,
+    },
+    /// Used to register new asset or to update its feeders, the submissions
+    /// of feeders that are no longer registered are removed
     RegisterFeed {
         symbol: String,
-        feeder: String,
+        feeders: Vec<String>,
+        min_submissions: u32,
+    },
+    /// Registers a symbol whose feeders are the members of a cw4 `group` contract, replacing its
+    /// feeders if it exists. With `weighted` the price is the median weighted by membership weight
+    RegisterGroupFeed {
+        symbol: String,
+        group: String,
+        min_submissions: u32,
+        weighted: bool,
+    },
+    /// Registers or replaces the feeders of multiple symbols at once
+    BulkRegisterFeeds {
+        feeds: Vec<FeedMsg>,
+    },
+    /// Removes a feed with its guards and the submissions of its feeders
+    RemoveFeed {
+        symbol: String,
+    },
+    /// Sets the number of prices kept in the history of the symbol, 0 disables the history
+    UpdateHistorySize {
+        symbol: String,
+        size: u32,
+    },
+    /// Sets the guards checked on every submission of the symbol, `None` disables a guard.
+    /// Submissions that violate a guard are skipped and reported in the `rejected` attribute
+    UpdateGuards {
+        symbol: String,
+        min_update_interval: Option<u64>,
+        max_change_bps: Option<u64>,
+        min_price: Option<Decimal>,
+        max_price: Option<Decimal>,
     },
+    /// Sets the number of seconds after its observation a submission of the symbol expires,
+    /// `None` disables the expiry. The price query fails once not enough submissions are live
+    UpdatePriceTtl {
+        symbol: String,
+        ttl: Option<u64>,
+    },
+    /// Feeder or delegate operation, a delegate submits the prices of the feeder that authorized it.
+    /// `prices` are observed at the block time, `timestamped_prices` carry their observation time
     FeedPrices {
         prices: Vec<(String, Decimal)>,
+        timestamped_prices: Option<Vec<FeedPrice>>,
+    },
+    /// Feeder operation to let `delegate` submit prices for all its symbols until `expires`.
+    /// A delegate can only be authorized by one feeder at a time
+    AuthorizeDelegate {
+        delegate: String,
+        expires: Option<u64>,
+    },
+    /// Enables the commit-reveal mode of the symbol with the provided periods, `None` disables it.
+    /// In commit-reveal mode prices are only submitted with `commit_prices` and `reveal_prices`
+    UpdateCommitReveal {
+        symbol: String,
+        commit_reveal: Option<CommitRevealMsg>,
+    },
+    /// Feeder or delegate operation to commit the sha256 hash of
+    /// "{feeder}:{symbol}:{round_id}:{price}:{salt}" for one or multiple symbols,
+    /// the first commit after a round ended opens a new round
+    CommitPrices {
+        commits: Vec<(String, Binary)>,
+    },
+    /// Feeder or delegate operation to reveal the committed prices during the reveal phase
+    RevealPrices {
+        reveals: Vec<RevealMsg>,
+    },
+    /// Finalizes the round of the symbol once its reveal phase is over, can be called by anyone
+    FinalizeRound {
+        symbol: String,
+    },
+    /// Revokes a delegate, can be called by the feeder that authorized it or the owner
+    RevokeDelegate {
+        delegate: String,
+    },
+    /// Submits price reports signed off-chain by the feeders, can be relayed by anyone.
+    /// Reports that fail verification or violate a guard are skipped and reported
+    /// in the `rejected` attribute
+    SubmitSignedPrices {
+        reports: Vec<SignedPriceReport>,
+    },
+    /// Owner operation that sets the submission of every feeder of the symbol to the
+    /// provided price without checking the guards, used for legitimate large moves
+    ForceUpdatePrices {
+        prices: Vec<(String, Decimal)>,
     },
 }
 
@@ -29,16 +131,293 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Base(ProxyQueryMsg),
     Config {},
-    Feeder { symbol: String },
+    Feeder {
+        symbol: String,
+    },
+    /// Registered feeds with the latest submission of each feeder
+    Feeds {
+        start_after: Option<String>, // symbol for pagination
+        limit: Option<u32>,
+    },
+    /// Symbols the feeder is registered for with its latest submission on each, only
+    /// feeds with a feeder list are covered as group memberships are not tracked
+    FeedersByAddress {
+        feeder: String,
+    },
+    /// Recorded prices of the symbol from oldest to newest
+    History {
+        symbol: String,
+        start_after: Option<u64>, // timestamp for pagination
+        limit: Option<u32>,
+    },
+    /// Time weighted average price over the last `window` seconds,
+    /// using the recorded history of the symbol. Returns a [`tefi_oracle::hub::TwapResponse`]
+    Twap {
+        symbol: String,
+        window: u64,
+    },
+    /// Latest submission of each feeder of the symbol
+    Submissions {
+        symbol: String,
+    },
+    /// Same as the `Price` query, also reporting the submission time of the submissions used
+    PriceDetail {
+        symbol: String,
+    },
+    Guards {
+        symbol: String,
+    },
+    /// Public key and last used nonce of a feeder
+    FeederKey {
+        feeder: String,
+    },
+    /// Commit-reveal configuration, open round and last closed round of the symbol
+    Round {
+        symbol: String,
+    },
+    /// Delegates authorized by a feeder
+    Delegates {
+        feeder: String,
+    },
+    /// Latest submission time of every feeder with the symbols its price expired on
+    Liveness {},
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FeedMsg {
+    pub symbol: String,
+    pub feeders: Vec<String>,
+    pub min_submissions: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CommitRevealMsg {
+    pub commit_period: u64,
+    pub reveal_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RevealMsg {
+    pub symbol: String,
+    pub price: Decimal,
+    pub salt: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FeedPrice {
+    pub symbol: String,
+    pub price: Decimal,
+    /// Time at which the price was observed
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SignedPriceReport {
+    pub feeder: String,
+    pub symbol: String,
+    pub price: Decimal,
+    pub timestamp: u64,
+    /// Must be greater than the last nonce used by the feeder
+    pub nonce: u64,
+    /// 64 bytes secp256k1 signature (r, s) of the sha256 hash of the JSON encoded [`PriceReport`]
+    pub signature: Binary,
+}
+
+/// Payload signed by the feeders, bound to the chain, the contract and the feeder to prevent
+/// the same report from being submitted somewhere else or on behalf of another feeder
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PriceReport {
+    pub chain_id: String,
+    pub contract: String,
+    pub feeder: String,
+    pub symbol: String,
+    pub price: Decimal,
+    pub timestamp: u64,
+    pub nonce: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
+    pub submission_window: Option<u64>,
+    pub max_observation_age: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct FeederResponse {
     pub symbol: String,
+    pub feeders: Vec<String>,
+    pub min_submissions: u32,
+    /// cw4 group whose members are the feeders, only set for group feeds
+    pub group: Option<String>,
+    pub weighted: bool,
+    pub ttl: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeedsResponse {
+    pub feeds: Vec<FeedResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeedResponse {
+    pub symbol: String,
+    pub feeders: Vec<String>,
+    pub min_submissions: u32,
+    pub group: Option<String>,
+    pub weighted: bool,
+    pub ttl: Option<u64>,
+    pub submissions: Vec<SubmissionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeedersByAddressResponse {
+    pub feeder: String,
+    pub feeds: Vec<FeederFeedResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeederFeedResponse {
+    pub symbol: String,
+    /// Latest submission of the feeder, if any
+    pub submission: Option<SubmissionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubmissionsResponse {
+    pub symbol: String,
+    pub submissions: Vec<SubmissionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubmissionResponse {
     pub feeder: String,
+    pub price: Decimal,
+    pub last_updated: u64,
+    pub last_submitted: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceDetailResponse {
+    pub symbol: String,
+    pub rate: Decimal,
+    /// Observation time of the oldest submission used
+    pub last_updated: u64,
+    /// Submission time of the oldest submission used
+    pub last_submitted: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GuardsResponse {
+    pub symbol: String,
+    pub min_update_interval: Option<u64>,
+    pub max_change_bps: Option<u64>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeederKeyResponse {
+    pub feeder: String,
+    pub public_key: Option<Binary>,
+    pub last_nonce: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoryResponse {
+    pub symbol: String,
+    pub observations: Vec<ObservationResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ObservationResponse {
+    pub rate: Decimal,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DelegatesResponse {
+    pub feeder: String,
+    pub delegates: Vec<DelegateResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DelegateResponse {
+    pub delegate: String,
+    pub expires: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LivenessResponse {
+    pub feeders: Vec<FeederLivenessResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeederLivenessResponse {
+    pub feeder: String,
+    /// Block time of the latest submission of the feeder on any symbol
+    pub last_submitted: Option<u64>,
+    /// Symbols with a ttl on which the feeder has no live submission
+    pub overdue: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundResponse {
+    pub symbol: String,
+    pub commit_period: u64,
+    pub reveal_period: u64,
+    pub current_round: Option<CurrentRoundResponse>,
+    pub last_round: Option<RoundResultResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurrentRoundResponse {
+    pub round_id: u64,
+    pub started_at: u64,
+    pub commit_end: u64,
+    pub reveal_end: u64,
+    pub committed: Vec<String>,
+    pub revealed: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundResultResponse {
+    pub round_id: u64,
+    pub rate: Option<Decimal>,
+    pub ended_at: u64,
+    pub revealed: Vec<String>,
+    pub non_revealers: Vec<String>,
+}
+
+/// Queries supported by the cw4 group contracts used as feeder sets
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw4QueryMsg {
+    /// Response: [`MemberListResponse`]
+    ListMembers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Response: [`MemberResponse`]
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MemberResponse {
+    /// `None` if the address is not a member of the group
+    pub weight: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MemberListResponse {
+    pub members: Vec<Member>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Member {
+    pub addr: String,
+    pub weight: u64,
 }
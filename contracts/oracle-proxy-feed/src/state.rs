@@ -1,25 +1,64 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Binary, Decimal, Empty};
 use cw_storage_plus::{Item, Map};
 use tefi_oracle::proxy::ProxyPriceResponse;
 
-use crate::msg::ConfigResponse;
+use crate::errors::SubmissionError;
+use crate::math::exceeds_max_change;
+use crate::msg::{
+    ConfigResponse, FeederResponse, GuardsResponse, RoundResultResponse, SubmissionResponse,
+};
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const FEEDERS: Map<&[u8], Addr> = Map::new("feeders");
-pub const PRICES: Map<&[u8], PriceInfo> = Map::new("prices");
+pub const FEEDS: Map<&[u8], Feed> = Map::new("feeds");
+// latest submission of each feeder, indexed by (symbol, feeder)
+pub const SUBMISSIONS: Map<(&[u8], &[u8]), PriceInfo> = Map::new("submissions");
+// symbols each feeder is registered for, indexed by (feeder, symbol)
+pub const FEEDER_FEEDS: Map<(&[u8], &[u8]), Empty> = Map::new("feeder_feeds");
+// feeder each delegate submits prices for, indexed by delegate
+pub const DELEGATES: Map<&[u8], Delegation> = Map::new("delegates");
+// delegates authorized by each feeder, indexed by (feeder, delegate)
+pub const FEEDER_DELEGATES: Map<(&[u8], &[u8]), Empty> = Map::new("feeder_delegates");
+// commit-reveal periods of the symbols that only accept commit-reveal submissions
+pub const COMMIT_REVEAL: Map<&[u8], CommitReveal> = Map::new("commit_reveal");
+// open commit-reveal round of each symbol
+pub const ROUNDS: Map<&[u8], Round> = Map::new("rounds");
+// commits of the open round, indexed by (symbol, feeder)
+pub const COMMITS: Map<(&[u8], &[u8]), Commit> = Map::new("commits");
+// outcome of the last closed round of each symbol
+pub const LAST_ROUNDS: Map<&[u8], RoundResult> = Map::new("last_rounds");
+// price of the last round of each symbol that finalized with enough reveals
+pub const FINALIZED_PRICES: Map<&[u8], FinalizedPrice> = Map::new("finalized_prices");
+pub const GUARDS: Map<&[u8], FeedGuards> = Map::new("guards");
+// seconds after its observation a submission of each symbol expires
+pub const PRICE_TTLS: Map<&[u8], u64> = Map::new("price_ttls");
+// feeder and price of each symbol stored by the single feeder version, moved by `migrate`
+pub const LEGACY_FEEDERS: Map<&[u8], Addr> = Map::new("feeders");
+pub const LEGACY_PRICES: Map<&[u8], PriceInfo> = Map::new("prices");
+// secp256k1 public key used to verify the signed reports of each feeder
+pub const FEEDER_KEYS: Map<&[u8], Binary> = Map::new("feeder_keys");
+// last nonce used in a signed report of each feeder
+pub const NONCES: Map<&[u8], u64> = Map::new("nonces");
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Config {
     pub owner: Addr,
+    // submissions older than this many seconds are ignored, all latest submissions are used if not set
+    pub submission_window: Option<u64>,
+    // prices observed more than this many seconds before their submission are rejected,
+    // observations of any age are accepted if not set
+    #[serde(default)]
+    pub max_observation_age: Option<u64>,
 }
 
 impl Config {
     pub fn as_res(&self) -> ConfigResponse {
         ConfigResponse {
             owner: self.owner.to_string(),
+            submission_window: self.submission_window,
+            max_observation_age: self.max_observation_age,
         }
     }
 
@@ -27,12 +66,197 @@ impl Config {
     pub fn is_owner(&self, addr: &Addr) -> bool {
         self.owner.eq(addr)
     }
+
+    /// Checks the observation `timestamp` of a price submitted at `now`
+    pub fn check_observation_time(&self, timestamp: u64, now: u64) -> Result<(), SubmissionError> {
+        if timestamp > now {
+            return Err(SubmissionError::FutureTimestamp { timestamp });
+        }
+
+        if let Some(max_observation_age) = self.max_observation_age {
+            if timestamp.saturating_add(max_observation_age) < now {
+                return Err(SubmissionError::TooOld {
+                    timestamp,
+                    max_observation_age,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks if a submission made at `last_updated_time` is still used at `now`
+    pub fn is_fresh(&self, last_updated_time: u64, now: u64) -> bool {
+        match self.submission_window {
+            Some(window) => last_updated_time.saturating_add(window) >= now,
+            None => true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Feed {
+    // empty for group feeds
+    pub feeders: Vec<Addr>,
+    // minimum number of fresh submissions required to return a price
+    pub min_submissions: u32,
+    // cw4 group whose members are the feeders, replaces the `feeders` list if set
+    #[serde(default)]
+    pub group: Option<Addr>,
+    // use the median weighted by membership weight, only for group feeds
+    #[serde(default)]
+    pub weighted: bool,
+}
+
+impl Feed {
+    pub fn as_res(&self, symbol: String, ttl: Option<u64>) -> FeederResponse {
+        FeederResponse {
+            symbol,
+            feeders: self.feeders.iter().map(|addr| addr.to_string()).collect(),
+            min_submissions: self.min_submissions,
+            group: self.group.as_ref().map(|group| group.to_string()),
+            weighted: self.weighted,
+            ttl,
+        }
+    }
+
+    /// Checks if the provided `addr` is in the feeder list of this feed, always false
+    /// for group feeds whose members are only known to the group contract
+    pub fn is_feeder(&self, addr: &Addr) -> bool {
+        self.feeders.contains(addr)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Delegation {
+    pub feeder: Addr,
+    // block time after which the delegate can no longer submit, never expires if not set
+    pub expires: Option<u64>,
+}
+
+impl Delegation {
+    /// Checks if the delegation can still be used at `now`
+    pub fn is_active(&self, now: u64) -> bool {
+        match self.expires {
+            Some(expires) => now < expires,
+            None => true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct FeedGuards {
+    // minimum seconds between two submissions of the same feeder
+    pub min_update_interval: Option<u64>,
+    // maximum change in basis points from the previous submission of the same feeder
+    pub max_change_bps: Option<u64>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+}
+
+impl FeedGuards {
+    pub fn as_res(&self, symbol: String) -> GuardsResponse {
+        GuardsResponse {
+            symbol,
+            min_update_interval: self.min_update_interval,
+            max_change_bps: self.max_change_bps,
+            min_price: self.min_price,
+            max_price: self.max_price,
+        }
+    }
+
+    /// Checks a new submission against the guards, given the `previous` submission
+    /// of the same feeder
+    pub fn check(
+        &self,
+        submission: &PriceInfo,
+        previous: Option<&PriceInfo>,
+    ) -> Result<(), SubmissionError> {
+        let price: Decimal = submission.price;
+        self.check_bounds(price)?;
+
+        if let Some(previous) = previous {
+            if submission.last_updated_time < previous.last_updated_time {
+                return Err(SubmissionError::OutOfOrder {
+                    timestamp: submission.last_updated_time,
+                    previous: previous.last_updated_time,
+                });
+            }
+
+            if let Some(min_update_interval) = self.min_update_interval {
+                if previous.submitted_time.saturating_add(min_update_interval)
+                    > submission.submitted_time
+                {
+                    return Err(SubmissionError::TooFrequent {
+                        min_update_interval,
+                    });
+                }
+            }
+
+            self.check_change(previous.price, price)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks a revealed price against the guards, given the `previous` finalized price of
+    /// the symbol. Rounds set the update frequency, so only the bounds and the max move apply
+    pub fn check_reveal(
+        &self,
+        price: Decimal,
+        previous: Option<Decimal>,
+    ) -> Result<(), SubmissionError> {
+        self.check_bounds(price)?;
+        if let Some(previous) = previous {
+            self.check_change(previous, price)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_bounds(&self, price: Decimal) -> Result<(), SubmissionError> {
+        if price.is_zero() {
+            return Err(SubmissionError::ZeroPrice {});
+        }
+
+        if let Some(min_price) = self.min_price {
+            if price < min_price {
+                return Err(SubmissionError::BelowMinimum { price, min_price });
+            }
+        }
+
+        if let Some(max_price) = self.max_price {
+            if price > max_price {
+                return Err(SubmissionError::AboveMaximum { price, max_price });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_change(&self, previous: Decimal, price: Decimal) -> Result<(), SubmissionError> {
+        if let Some(max_change_bps) = self.max_change_bps {
+            if exceeds_max_change(previous, price, max_change_bps) {
+                return Err(SubmissionError::MaxMoveExceeded {
+                    previous,
+                    price,
+                    max_change_bps,
+                });
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PriceInfo {
     pub price: Decimal,
+    // time at which the price was observed
     pub last_updated_time: u64,
+    // block time at which the price was submitted, 0 for submissions stored before it was recorded
+    #[serde(default)]
+    pub submitted_time: u64,
 }
 
 impl PriceInfo {
@@ -42,4 +266,81 @@ impl PriceInfo {
             last_updated: self.last_updated_time,
         }
     }
+
+    pub fn as_submission_res(&self, feeder: String) -> SubmissionResponse {
+        SubmissionResponse {
+            feeder,
+            price: self.price,
+            last_updated: self.last_updated_time,
+            last_submitted: self.submitted_time,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CommitReveal {
+    pub commit_period: u64,
+    pub reveal_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Round {
+    pub id: u64,
+    pub started_at: u64,
+}
+
+impl Round {
+    /// Returns the time at which the commit phase ends and the reveal phase starts
+    pub fn commit_end(&self, commit_reveal: &CommitReveal) -> u64 {
+        self.started_at.saturating_add(commit_reveal.commit_period)
+    }
+
+    /// Returns the time at which the reveal phase ends and the round can be finalized
+    pub fn reveal_end(&self, commit_reveal: &CommitReveal) -> u64 {
+        self.commit_end(commit_reveal)
+            .saturating_add(commit_reveal.reveal_period)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Commit {
+    pub round_id: u64,
+    // sha256 hash of "{feeder}:{symbol}:{round_id}:{price}:{salt}"
+    pub hash: Binary,
+    // set once the price is revealed
+    pub price: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoundResult {
+    pub round_id: u64,
+    // median of the reveals, not set if there were not enough reveals
+    pub rate: Option<Decimal>,
+    pub ended_at: u64,
+    pub revealed: Vec<Addr>,
+    // feeders that committed without revealing
+    pub non_revealers: Vec<Addr>,
+}
+
+impl RoundResult {
+    pub fn as_res(&self) -> RoundResultResponse {
+        RoundResultResponse {
+            round_id: self.round_id,
+            rate: self.rate,
+            ended_at: self.ended_at,
+            revealed: self.revealed.iter().map(|addr| addr.to_string()).collect(),
+            non_revealers: self
+                .non_revealers
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FinalizedPrice {
+    pub round_id: u64,
+    pub rate: Decimal,
+    pub finalized_at: u64,
 }
@@ -0,0 +1,105 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::msg::{Cw4QueryMsg, Member, MemberListResponse, MemberResponse};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        api: MockApi::default(),
+        storage: MockStorage::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    // members of each cw4 group with their weight, sorted by address
+    groups: HashMap<String, BTreeMap<String, u64>>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                let members = match self.groups.get(contract_addr) {
+                    Some(members) => members,
+                    None => {
+                        return SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.to_string(),
+                        })
+                    }
+                };
+
+                let res = match from_binary(msg).unwrap() {
+                    Cw4QueryMsg::Member { addr, .. } => to_binary(&MemberResponse {
+                        weight: members.get(&addr).copied(),
+                    }),
+                    Cw4QueryMsg::ListMembers { start_after, limit } => {
+                        let members: Vec<Member> = members
+                            .iter()
+                            .filter(|(addr, _)| match &start_after {
+                                Some(start_after) => *addr > start_after,
+                                None => true,
+                            })
+                            .take(limit.unwrap_or(10) as usize)
+                            .map(|(addr, weight)| Member {
+                                addr: addr.to_string(),
+                                weight: *weight,
+                            })
+                            .collect();
+                        to_binary(&MemberListResponse { members })
+                    }
+                };
+
+                SystemResult::Ok(ContractResult::from(res))
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            groups: HashMap::new(),
+        }
+    }
+
+    // configure a mock cw4 group contract with (member, weight) pairs
+    pub fn with_group(&mut self, addr: &str, members: &[(&str, u64)]) {
+        self.groups.insert(
+            addr.to_string(),
+            members
+                .iter()
+                .map(|(member, weight)| (member.to_string(), *weight))
+                .collect(),
+        );
+    }
+}
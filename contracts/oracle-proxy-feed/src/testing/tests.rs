@@ -0,0 +1,2084 @@
+use crate::contract::{commit_hash, execute, instantiate, migrate, query, report_hash};
+use crate::errors::{ReportError, SubmissionError};
+use crate::msg::{
+    CommitRevealMsg, ConfigResponse, CurrentRoundResponse, DelegateResponse, DelegatesResponse,
+    ExecuteMsg, FeedMsg, FeedPrice, FeedResponse, FeederFeedResponse, FeederKeyResponse,
+    FeederLivenessResponse, FeederResponse, FeedersByAddressResponse, FeedsResponse,
+    GuardsResponse, HistoryResponse, InstantiateMsg, LivenessResponse, MigrateMsg,
+    ObservationResponse, PriceDetailResponse, PriceReport, QueryMsg, RevealMsg, RoundResponse,
+    RoundResultResponse, SignedPriceReport, SubmissionResponse, SubmissionsResponse,
+};
+use crate::state::{Feed, LEGACY_FEEDERS, LEGACY_PRICES};
+use crate::testing::mock_querier::{mock_dependencies, WasmMockQuerier};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi};
+use cosmwasm_std::{
+    attr, from_binary, from_slice, to_vec, Addr, Binary, Decimal, Env, MemoryStorage, OwnedDeps,
+    Response, Timestamp,
+};
+use cw_storage_plus::{Item, Map};
+use k256::ecdsa::signature::DigestSigner;
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use tefi_oracle::errors::ContractError;
+use tefi_oracle::hub::{TwapResponse, MAX_HISTORY_SIZE};
+use tefi_oracle::proxy::{ProxyPriceResponse, ProxyQueryMsg};
+
+const OWNER_ADDR: &str = "owner_0001";
+const FEEDER_ADDR_1: &str = "feeder_0001";
+const FEEDER_ADDR_2: &str = "feeder_0002";
+const FEEDER_ADDR_3: &str = "feeder_0003";
+
+// helper to successfully init
+pub fn init(
+    deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+) -> Result<Response, ContractError> {
+    let msg = InstantiateMsg {
+        owner: OWNER_ADDR.to_string(),
+        submission_window: Some(600u64),
+        max_observation_age: Some(60u64),
+    };
+    let info = mock_info(OWNER_ADDR, &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg)
+}
+
+// helper to build an env at the given block time
+pub fn env_at(now: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(now);
+    env
+}
+
+// helper to register the feeders of a symbol
+pub fn register_feed(
+    deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+    symbol: &str,
+    feeders: Vec<&str>,
+    min_submissions: u32,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::RegisterFeed {
+        symbol: symbol.to_string(),
+        feeders: feeders
+            .into_iter()
+            .map(|feeder| feeder.to_string())
+            .collect(),
+        min_submissions,
+    };
+    let info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg)
+}
+
+// helper to feed a price at the given block time
+pub fn feed_price(
+    deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+    feeder: &str,
+    symbol: &str,
+    price: &str,
+    now: u64,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::FeedPrices {
+        prices: vec![(symbol.to_string(), Decimal::from_str(price).unwrap())],
+        timestamped_prices: None,
+    };
+    let info = mock_info(feeder, &[]);
+    execute(deps.as_mut(), env_at(now), info, msg)
+}
+
+// helper to build a report for the mock contract signed with `key`
+pub fn sign_report(
+    key: &SigningKey,
+    feeder: &str,
+    symbol: &str,
+    price: &str,
+    timestamp: u64,
+    nonce: u64,
+) -> SignedPriceReport {
+    let env = mock_env();
+    let report = PriceReport {
+        chain_id: env.block.chain_id,
+        contract: env.contract.address.to_string(),
+        feeder: feeder.to_string(),
+        symbol: symbol.to_string(),
+        price: Decimal::from_str(price).unwrap(),
+        timestamp,
+        nonce,
+    };
+    let digest = Sha256::new().chain(to_vec(&report).unwrap());
+    assert_eq!(
+        digest.clone().finalize().to_vec(),
+        report_hash(&report).unwrap()
+    );
+    let signature: Signature = key.sign_digest(digest);
+
+    SignedPriceReport {
+        feeder: feeder.to_string(),
+        symbol: report.symbol,
+        price: report.price,
+        timestamp,
+        nonce,
+        signature: Binary::from(signature.as_ref()),
+    }
+}
+
+// helper to query the price of a symbol at the given block time
+pub fn query_price(
+    deps: &OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+    symbol: &str,
+    now: u64,
+) -> Result<ProxyPriceResponse, ContractError> {
+    let msg = QueryMsg::Base(ProxyQueryMsg::Price {
+        symbol: symbol.to_string(),
+    });
+    query(deps.as_ref(), env_at(now), msg).map(|res| from_binary(&res).unwrap())
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config_res,
+        ConfigResponse {
+            owner: OWNER_ADDR.to_string(),
+            submission_window: Some(600u64),
+            max_observation_age: Some(60u64),
+        }
+    );
+
+    // update the window
+    let msg = ExecuteMsg::UpdateSubmissionWindow {
+        submission_window: None,
+    };
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.submission_window, None);
+
+    // update the maximum report age
+    let msg = ExecuteMsg::UpdateMaxObservationAge {
+        max_observation_age: Some(120u64),
+    };
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config_res.max_observation_age, Some(120u64));
+}
+
+#[test]
+fn test_register_feed() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    // unauthorized attempt
+    let msg = ExecuteMsg::RegisterFeed {
+        symbol: "LUNA".to_string(),
+        feeders: vec![FEEDER_ADDR_1.to_string()],
+        min_submissions: 1,
+    };
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // invalid minimum submissions
+    let err = register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1], 2).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Minimum submissions must be between 1 and the number of feeders".to_string()
+        }
+    );
+    assert!(register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1], 0).is_err());
+
+    // duplicated feeder
+    let err = register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1, FEEDER_ADDR_1], 1).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: format!("Feeder {} is provided more than once", FEEDER_ADDR_1)
+        }
+    );
+
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1, FEEDER_ADDR_2], 2).unwrap();
+
+    let msg = QueryMsg::Feeder {
+        symbol: "LUNA".to_string(),
+    };
+    let res: FeederResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FeederResponse {
+            symbol: "LUNA".to_string(),
+            feeders: vec![FEEDER_ADDR_1.to_string(), FEEDER_ADDR_2.to_string()],
+            min_submissions: 2,
+            group: None,
+            weighted: false,
+            ttl: None,
+        }
+    );
+
+    // submissions of removed feeders are dropped
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "80", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_2, "LUNA", "81", 1000).unwrap();
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_2, FEEDER_ADDR_3], 1).unwrap();
+
+    let msg = QueryMsg::Submissions {
+        symbol: "LUNA".to_string(),
+    };
+    let res: SubmissionsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        SubmissionsResponse {
+            symbol: "LUNA".to_string(),
+            submissions: vec![SubmissionResponse {
+                feeder: FEEDER_ADDR_2.to_string(),
+                price: Decimal::from_str("81").unwrap(),
+                last_updated: 1000u64,
+                last_submitted: 1000u64,
+            }]
+        }
+    );
+}
+
+#[test]
+fn test_median_price() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    register_feed(
+        &mut deps,
+        "LUNA",
+        vec![FEEDER_ADDR_1, FEEDER_ADDR_2, FEEDER_ADDR_3],
+        2,
+    )
+    .unwrap();
+
+    // not a registered feeder
+    let err = feed_price(&mut deps, "notfeeder0000", "LUNA", "80", 1000).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // symbol without feed
+    let err = feed_price(&mut deps, FEEDER_ADDR_1, "MIR", "80", 1000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "There is no feeder registered for the provided symbol".to_string()
+        }
+    );
+
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "80", 1000).unwrap();
+    let err = query_price(&deps, "LUNA", 1000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Not enough fresh submissions for the requested symbol (1 of 2)".to_string()
+        }
+    );
+
+    // even count uses the average of the middle prices
+    feed_price(&mut deps, FEEDER_ADDR_2, "LUNA", "90", 1200).unwrap();
+    assert_eq!(
+        query_price(&deps, "LUNA", 1200).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("85").unwrap(),
+            last_updated: 1000u64,
+        }
+    );
+
+    // a single outlier does not move the median
+    feed_price(&mut deps, FEEDER_ADDR_3, "LUNA", "1000000", 1300).unwrap();
+    assert_eq!(
+        query_price(&deps, "LUNA", 1300).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("90").unwrap(),
+            last_updated: 1000u64,
+        }
+    );
+
+    // the first submission leaves the window
+    assert_eq!(
+        query_price(&deps, "LUNA", 1601).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("500045").unwrap(),
+            last_updated: 1200u64,
+        }
+    );
+
+    let err = query_price(&deps, "LUNA", 1801).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Not enough fresh submissions for the requested symbol (1 of 2)".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_guards() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1, FEEDER_ADDR_2], 1).unwrap();
+
+    // zero prices are always rejected
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "0", 1000).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "price_feed"),
+            attr("rejected", "LUNA"),
+            attr("reason", SubmissionError::ZeroPrice {}.to_string()),
+        ]
+    );
+
+    let msg = ExecuteMsg::UpdateGuards {
+        symbol: "LUNA".to_string(),
+        min_update_interval: Some(60u64),
+        max_change_bps: Some(1000u64),
+        min_price: Some(Decimal::from_str("1").unwrap()),
+        max_price: Some(Decimal::from_str("1000").unwrap()),
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let msg = QueryMsg::Guards {
+        symbol: "LUNA".to_string(),
+    };
+    let res: GuardsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        GuardsResponse {
+            symbol: "LUNA".to_string(),
+            min_update_interval: Some(60u64),
+            max_change_bps: Some(1000u64),
+            min_price: Some(Decimal::from_str("1").unwrap()),
+            max_price: Some(Decimal::from_str("1000").unwrap()),
+        }
+    );
+
+    // out of range
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "0.5", 1000).unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr("reason", "Price 0.5 is below the minimum of 1".to_string())
+    );
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "1001", 1000).unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr(
+            "reason",
+            "Price 1001 is above the maximum of 1000".to_string()
+        )
+    );
+
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "100", 1000).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "price_feed"),
+            attr("symbol", "LUNA"),
+            attr("price", "100"),
+        ]
+    );
+
+    // too frequent
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "101", 1059).unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr(
+            "reason",
+            SubmissionError::TooFrequent {
+                min_update_interval: 60u64
+            }
+            .to_string()
+        )
+    );
+
+    // moves more than 10%
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "110.01", 1060).unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr(
+            "reason",
+            "Price 110.01 moves more than 1000 bps from the previous price 100".to_string()
+        )
+    );
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "90", 1060).unwrap();
+    assert_eq!(
+        query_price(&deps, "LUNA", 1060).unwrap().rate,
+        Decimal::from_str("90").unwrap()
+    );
+
+    // the guards are checked against the previous price of the same feeder only
+    feed_price(&mut deps, FEEDER_ADDR_2, "LUNA", "200", 1060).unwrap();
+
+    // legitimate large move by the owner
+    let msg = ExecuteMsg::ForceUpdatePrices {
+        prices: vec![("LUNA".to_string(), Decimal::from_str("300").unwrap())],
+    };
+    let info = mock_info(FEEDER_ADDR_1, &[]);
+    let err = execute(deps.as_mut(), env_at(1100), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), env_at(1100), owner_info, msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "force_update_prices"),
+            attr("symbol", "LUNA"),
+            attr("price", "300"),
+        ]
+    );
+    assert_eq!(
+        query_price(&deps, "LUNA", 1100).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("300").unwrap(),
+            last_updated: 1100u64,
+        }
+    );
+
+    // feeders continue from the forced price
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "310", 1160).unwrap();
+    assert_eq!(res.attributes[1], attr("symbol", "LUNA"));
+}
+
+#[test]
+fn test_signed_prices() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1, FEEDER_ADDR_2], 1).unwrap();
+
+    let key_1 = SigningKey::from_bytes(&[1u8; 32]).unwrap();
+    let key_2 = SigningKey::from_bytes(&[2u8; 32]).unwrap();
+    let public_key_1 = Binary::from(VerifyingKey::from(&key_1).to_bytes().to_vec());
+    let public_key_2 = Binary::from(VerifyingKey::from(&key_2).to_bytes().to_vec());
+
+    // reports of feeders without a public key are rejected
+    let report_msg = ExecuteMsg::SubmitSignedPrices {
+        reports: vec![sign_report(&key_1, FEEDER_ADDR_1, "LUNA", "100", 1000, 1)],
+    };
+    let info = mock_info("relayer_0001", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env_at(1010),
+        info.clone(),
+        report_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "submit_signed_prices"),
+            attr("rejected", "LUNA"),
+            attr(
+                "reason",
+                ReportError::MissingPublicKey {
+                    feeder: FEEDER_ADDR_1.to_string()
+                }
+                .to_string()
+            ),
+        ]
+    );
+
+    // register the keys
+    let keys_msg = ExecuteMsg::SetFeederKeys {
+        keys: vec![(FEEDER_ADDR_1.to_string(), Some(public_key_1.clone()))],
+    };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), keys_msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        owner_info.clone(),
+        ExecuteMsg::SetFeederKeys {
+            keys: vec![(FEEDER_ADDR_2.to_string(), Some(Binary::from(vec![2u8; 32])))],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Invalid public key for feeder feeder_0002".to_string()
+        }
+    );
+
+    // 33 bytes key with an invalid prefix
+    let mut invalid_key: Vec<u8> = public_key_2.to_vec();
+    invalid_key[0] = 0x04;
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        owner_info.clone(),
+        ExecuteMsg::SetFeederKeys {
+            keys: vec![(FEEDER_ADDR_2.to_string(), Some(Binary::from(invalid_key)))],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Invalid public key for feeder feeder_0002".to_string()
+        }
+    );
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), keys_msg).unwrap();
+
+    // a key can not be shared between feeders
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        owner_info.clone(),
+        ExecuteMsg::SetFeederKeys {
+            keys: vec![(FEEDER_ADDR_2.to_string(), Some(public_key_1.clone()))],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Public key is already registered for feeder feeder_0001".to_string()
+        }
+    );
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        owner_info,
+        ExecuteMsg::SetFeederKeys {
+            keys: vec![(FEEDER_ADDR_2.to_string(), Some(public_key_2))],
+        },
+    )
+    .unwrap();
+
+    // anyone can relay a valid report
+    let res = execute(
+        deps.as_mut(),
+        env_at(1010),
+        info.clone(),
+        report_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "submit_signed_prices"),
+            attr("symbol", "LUNA"),
+            attr("price", "100"),
+        ]
+    );
+    assert_eq!(
+        query_price(&deps, "LUNA", 1010).unwrap().rate,
+        Decimal::from_str("100").unwrap()
+    );
+
+    let msg = QueryMsg::FeederKey {
+        feeder: FEEDER_ADDR_1.to_string(),
+    };
+    let res: FeederKeyResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FeederKeyResponse {
+            feeder: FEEDER_ADDR_1.to_string(),
+            public_key: Some(public_key_1),
+            last_nonce: Some(1u64),
+        }
+    );
+
+    // the same report can not be replayed
+    let res = execute(deps.as_mut(), env_at(1020), info.clone(), report_msg).unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr(
+            "reason",
+            ReportError::NonceUsed {
+                nonce: 1u64,
+                last_nonce: 1u64
+            }
+            .to_string()
+        )
+    );
+
+    // rejected reports
+    let reports = vec![
+        // signed with another key
+        sign_report(&key_2, FEEDER_ADDR_1, "LUNA", "101", 1020, 2),
+        // tampered price
+        SignedPriceReport {
+            price: Decimal::from_str("200").unwrap(),
+            ..sign_report(&key_1, FEEDER_ADDR_1, "LUNA", "101", 1020, 2)
+        },
+        // report of another feeder
+        SignedPriceReport {
+            feeder: FEEDER_ADDR_2.to_string(),
+            ..sign_report(&key_2, FEEDER_ADDR_1, "LUNA", "101", 1020, 2)
+        },
+        // too old
+        sign_report(&key_1, FEEDER_ADDR_1, "LUNA", "101", 959, 2),
+        // in the future
+        sign_report(&key_1, FEEDER_ADDR_1, "LUNA", "101", 1021, 3),
+        // not a feeder of the symbol
+        sign_report(&key_1, FEEDER_ADDR_3, "LUNA", "101", 1020, 2),
+    ];
+    let msg = ExecuteMsg::SubmitSignedPrices { reports };
+    let res = execute(deps.as_mut(), env_at(1020), info.clone(), msg).unwrap();
+    let reasons: Vec<String> = res
+        .attributes
+        .into_iter()
+        .filter(|attr| attr.key == "reason")
+        .map(|attr| attr.value)
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            ReportError::InvalidSignature {}.to_string(),
+            ReportError::InvalidSignature {}.to_string(),
+            ReportError::InvalidSignature {}.to_string(),
+            SubmissionError::TooOld {
+                timestamp: 959u64,
+                max_observation_age: 60u64
+            }
+            .to_string(),
+            SubmissionError::FutureTimestamp { timestamp: 1021u64 }.to_string(),
+            ReportError::NotFeeder {
+                feeder: FEEDER_ADDR_3.to_string()
+            }
+            .to_string(),
+        ]
+    );
+
+    // the nonces of the expired reports were not used
+    let msg = QueryMsg::FeederKey {
+        feeder: FEEDER_ADDR_1.to_string(),
+    };
+    let res: FeederKeyResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.last_nonce, Some(1u64));
+
+    // a newer nonce is accepted
+    let msg = ExecuteMsg::SubmitSignedPrices {
+        reports: vec![sign_report(&key_1, FEEDER_ADDR_1, "LUNA", "101", 1020, 2)],
+    };
+    execute(deps.as_mut(), env_at(1020), info, msg).unwrap();
+    assert_eq!(
+        query_price(&deps, "LUNA", 1020).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("101").unwrap(),
+            last_updated: 1020u64,
+        }
+    );
+}
+
+#[test]
+fn test_observation_timestamps() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1, FEEDER_ADDR_2], 2).unwrap();
+
+    // prices without observation time keep their (symbol, price) format
+    let msg: ExecuteMsg = from_slice(br#"{"feed_prices":{"prices":[["LUNA","100"]]}}"#).unwrap();
+    assert_eq!(
+        msg,
+        ExecuteMsg::FeedPrices {
+            prices: vec![("LUNA".to_string(), Decimal::from_str("100").unwrap())],
+            timestamped_prices: None,
+        }
+    );
+
+    let feed_msg = |price: &str, timestamp: u64| ExecuteMsg::FeedPrices {
+        prices: vec![],
+        timestamped_prices: Some(vec![FeedPrice {
+            symbol: "LUNA".to_string(),
+            price: Decimal::from_str(price).unwrap(),
+            timestamp,
+        }]),
+    };
+    let info = mock_info(FEEDER_ADDR_1, &[]);
+
+    // observed in the future
+    let res = execute(
+        deps.as_mut(),
+        env_at(1000),
+        info.clone(),
+        feed_msg("100", 1001),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr(
+            "reason",
+            SubmissionError::FutureTimestamp { timestamp: 1001u64 }.to_string()
+        )
+    );
+
+    // observed more than 60 seconds before the submission
+    let res = execute(
+        deps.as_mut(),
+        env_at(1000),
+        info.clone(),
+        feed_msg("100", 939),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr(
+            "reason",
+            SubmissionError::TooOld {
+                timestamp: 939u64,
+                max_observation_age: 60u64
+            }
+            .to_string()
+        )
+    );
+
+    execute(
+        deps.as_mut(),
+        env_at(1000),
+        info.clone(),
+        feed_msg("100", 950),
+    )
+    .unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_2, "LUNA", "102", 1005).unwrap();
+
+    // the observation time is reported as the update time
+    assert_eq!(
+        query_price(&deps, "LUNA", 1010).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("101").unwrap(),
+            last_updated: 950u64,
+        }
+    );
+
+    let msg = QueryMsg::PriceDetail {
+        symbol: "LUNA".to_string(),
+    };
+    let res: PriceDetailResponse =
+        from_binary(&query(deps.as_ref(), env_at(1010), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        PriceDetailResponse {
+            symbol: "LUNA".to_string(),
+            rate: Decimal::from_str("101").unwrap(),
+            last_updated: 950u64,
+            last_submitted: 1000u64,
+        }
+    );
+
+    // an observation older than the previous one of the feeder is rejected
+    let res = execute(deps.as_mut(), env_at(1005), info, feed_msg("99", 949)).unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr(
+            "reason",
+            SubmissionError::OutOfOrder {
+                timestamp: 949u64,
+                previous: 950u64
+            }
+            .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_feed_lifecycle() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    let msg = ExecuteMsg::BulkRegisterFeeds {
+        feeds: vec![
+            FeedMsg {
+                symbol: "LUNA".to_string(),
+                feeders: vec![FEEDER_ADDR_1.to_string(), FEEDER_ADDR_2.to_string()],
+                min_submissions: 1,
+            },
+            FeedMsg {
+                symbol: "MIR".to_string(),
+                feeders: vec![FEEDER_ADDR_1.to_string()],
+                min_submissions: 1,
+            },
+            FeedMsg {
+                symbol: "ANC".to_string(),
+                feeders: vec![FEEDER_ADDR_2.to_string()],
+                min_submissions: 1,
+            },
+        ],
+    };
+
+    // unauthorized attempt
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // an invalid feed fails the whole message
+    let info = mock_info(OWNER_ADDR, &[]);
+    let invalid_msg = ExecuteMsg::BulkRegisterFeeds {
+        feeds: vec![FeedMsg {
+            symbol: "LUNA".to_string(),
+            feeders: vec![FEEDER_ADDR_1.to_string()],
+            min_submissions: 2,
+        }],
+    };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), invalid_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Minimum submissions must be between 1 and the number of feeders".to_string()
+        }
+    );
+
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "100", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_1, "MIR", "3", 1000).unwrap();
+
+    let luna_submission = SubmissionResponse {
+        feeder: FEEDER_ADDR_1.to_string(),
+        price: Decimal::from_str("100").unwrap(),
+        last_updated: 1000u64,
+        last_submitted: 1000u64,
+    };
+    let mir_submission = SubmissionResponse {
+        feeder: FEEDER_ADDR_1.to_string(),
+        price: Decimal::from_str("3").unwrap(),
+        last_updated: 1000u64,
+        last_submitted: 1000u64,
+    };
+
+    // paginated feeds
+    let msg = QueryMsg::Feeds {
+        start_after: None,
+        limit: Some(2u32),
+    };
+    let res: FeedsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FeedsResponse {
+            feeds: vec![
+                FeedResponse {
+                    symbol: "ANC".to_string(),
+                    feeders: vec![FEEDER_ADDR_2.to_string()],
+                    min_submissions: 1,
+                    group: None,
+                    weighted: false,
+                    ttl: None,
+                    submissions: vec![],
+                },
+                FeedResponse {
+                    symbol: "LUNA".to_string(),
+                    feeders: vec![FEEDER_ADDR_1.to_string(), FEEDER_ADDR_2.to_string()],
+                    min_submissions: 1,
+                    group: None,
+                    weighted: false,
+                    ttl: None,
+                    submissions: vec![luna_submission.clone()],
+                },
+            ]
+        }
+    );
+
+    let msg = QueryMsg::Feeds {
+        start_after: Some("LUNA".to_string()),
+        limit: None,
+    };
+    let res: FeedsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.feeds,
+        vec![FeedResponse {
+            symbol: "MIR".to_string(),
+            feeders: vec![FEEDER_ADDR_1.to_string()],
+            min_submissions: 1,
+            group: None,
+            weighted: false,
+            ttl: None,
+            submissions: vec![mir_submission.clone()],
+        }]
+    );
+
+    let msg = QueryMsg::FeedersByAddress {
+        feeder: FEEDER_ADDR_1.to_string(),
+    };
+    let res: FeedersByAddressResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FeedersByAddressResponse {
+            feeder: FEEDER_ADDR_1.to_string(),
+            feeds: vec![
+                FeederFeedResponse {
+                    symbol: "LUNA".to_string(),
+                    submission: Some(luna_submission),
+                },
+                FeederFeedResponse {
+                    symbol: "MIR".to_string(),
+                    submission: Some(mir_submission),
+                },
+            ]
+        }
+    );
+
+    // remove a feed
+    let remove_msg = ExecuteMsg::RemoveFeed {
+        symbol: "LUNA".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(FEEDER_ADDR_1, &[]),
+        remove_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(deps.as_mut(), mock_env(), info.clone(), remove_msg.clone()).unwrap();
+
+    let err = query_price(&deps, "LUNA", 1000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "There is no feeder registered for the provided symbol".to_string()
+        }
+    );
+    let err = execute(deps.as_mut(), mock_env(), info, remove_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "There is no feeder registered for the provided symbol".to_string()
+        }
+    );
+
+    let res: FeedersByAddressResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.feeds
+            .into_iter()
+            .map(|feed| feed.symbol)
+            .collect::<Vec<String>>(),
+        vec!["MIR".to_string()]
+    );
+
+    // registering the symbol again starts without submissions
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1], 1).unwrap();
+    let msg = QueryMsg::Submissions {
+        symbol: "LUNA".to_string(),
+    };
+    let res: SubmissionsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.submissions, vec![]);
+}
+
+#[test]
+fn test_history_and_twap() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1, FEEDER_ADDR_2], 1).unwrap();
+
+    // the history is disabled by default
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "10", 900).unwrap();
+    let twap_msg = |window: u64| QueryMsg::Twap {
+        symbol: "LUNA".to_string(),
+        window,
+    };
+    let err = query(deps.as_ref(), env_at(1000), twap_msg(10)).unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+
+    let msg = ExecuteMsg::UpdateHistorySize {
+        symbol: "LUNA".to_string(),
+        size: 3,
+    };
+    let info = mock_info("notowner0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info(OWNER_ADDR, &[]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        ExecuteMsg::UpdateHistorySize {
+            symbol: "LUNA".to_string(),
+            size: MAX_HISTORY_SIZE + 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidHistorySize {
+            max: MAX_HISTORY_SIZE
+        }
+    );
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    // the median is recorded after every accepted submission
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "10", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_2, "LUNA", "12", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "28", 1100).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_2, "LUNA", "32", 1200).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "48", 1300).unwrap();
+
+    let msg = QueryMsg::History {
+        symbol: "LUNA".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: HistoryResponse =
+        from_binary(&query(deps.as_ref(), env_at(1300), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        HistoryResponse {
+            symbol: "LUNA".to_string(),
+            observations: vec![
+                ObservationResponse {
+                    rate: Decimal::from_str("20").unwrap(),
+                    timestamp: 1100u64,
+                },
+                ObservationResponse {
+                    rate: Decimal::from_str("30").unwrap(),
+                    timestamp: 1200u64,
+                },
+                ObservationResponse {
+                    rate: Decimal::from_str("40").unwrap(),
+                    timestamp: 1300u64,
+                },
+            ]
+        }
+    );
+
+    let msg = QueryMsg::History {
+        symbol: "LUNA".to_string(),
+        start_after: Some(1100u64),
+        limit: Some(1u32),
+    };
+    let res: HistoryResponse =
+        from_binary(&query(deps.as_ref(), env_at(1300), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.observations,
+        vec![ObservationResponse {
+            rate: Decimal::from_str("30").unwrap(),
+            timestamp: 1200u64,
+        }]
+    );
+
+    // prices are interpolated between observations, the latest one holds until the block time
+    let res: TwapResponse =
+        from_binary(&query(deps.as_ref(), env_at(1400), twap_msg(200)).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        TwapResponse {
+            symbol: "LUNA".to_string(),
+            rate: Decimal::from_str("37.5").unwrap(),
+            window: 200u64,
+            last_updated: 1300u64,
+        }
+    );
+    let res: TwapResponse =
+        from_binary(&query(deps.as_ref(), env_at(1400), twap_msg(250)).unwrap()).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("35.5").unwrap());
+    let res: TwapResponse =
+        from_binary(&query(deps.as_ref(), env_at(1300), twap_msg(0)).unwrap()).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("40").unwrap());
+
+    // the window must be covered by the history
+    let err = query(deps.as_ref(), env_at(1400), twap_msg(301)).unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+
+    // forced prices are recorded too
+    let msg = ExecuteMsg::ForceUpdatePrices {
+        prices: vec![("LUNA".to_string(), Decimal::from_str("50").unwrap())],
+    };
+    execute(deps.as_mut(), env_at(1400), info.clone(), msg).unwrap();
+    let res: TwapResponse =
+        from_binary(&query(deps.as_ref(), env_at(1500), twap_msg(200)).unwrap()).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("47.5").unwrap());
+
+    // the latest observation is not extended past the ttl
+    let msg = ExecuteMsg::UpdatePriceTtl {
+        symbol: "LUNA".to_string(),
+        ttl: Some(99u64),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let err = query(deps.as_ref(), env_at(1500), twap_msg(200)).unwrap_err();
+    assert_eq!(err, ContractError::HistoryNotAvailable {});
+    let msg = ExecuteMsg::UpdatePriceTtl {
+        symbol: "LUNA".to_string(),
+        ttl: Some(100u64),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let res: TwapResponse =
+        from_binary(&query(deps.as_ref(), env_at(1500), twap_msg(200)).unwrap()).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("47.5").unwrap());
+
+    // disabling the history clears it
+    let msg = ExecuteMsg::UpdateHistorySize {
+        symbol: "LUNA".to_string(),
+        size: 0,
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let msg = QueryMsg::History {
+        symbol: "LUNA".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: HistoryResponse =
+        from_binary(&query(deps.as_ref(), env_at(1500), msg).unwrap()).unwrap();
+    assert_eq!(res.observations, vec![]);
+}
+
+#[test]
+fn test_delegates() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1, FEEDER_ADDR_2], 1).unwrap();
+    register_feed(&mut deps, "MIR", vec![FEEDER_ADDR_1], 1).unwrap();
+
+    const DELEGATE_ADDR: &str = "delegate_0001";
+    let authorize_msg = |expires: Option<u64>| ExecuteMsg::AuthorizeDelegate {
+        delegate: DELEGATE_ADDR.to_string(),
+        expires,
+    };
+
+    // only feeders can authorize delegates
+    let err = execute(
+        deps.as_mut(),
+        env_at(1000),
+        mock_info(OWNER_ADDR, &[]),
+        authorize_msg(None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info(FEEDER_ADDR_1, &[]);
+    let err = execute(
+        deps.as_mut(),
+        env_at(1000),
+        info.clone(),
+        authorize_msg(Some(1000u64)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Delegation expiry must be in the future".to_string()
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(1000),
+        info.clone(),
+        authorize_msg(Some(2000u64)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "authorize_delegate"),
+            attr("feeder", FEEDER_ADDR_1),
+            attr("delegate", DELEGATE_ADDR),
+        ]
+    );
+
+    let delegates_msg = |feeder: &str| QueryMsg::Delegates {
+        feeder: feeder.to_string(),
+    };
+    let res: DelegatesResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), delegates_msg(FEEDER_ADDR_1)).unwrap())
+            .unwrap();
+    assert_eq!(
+        res,
+        DelegatesResponse {
+            feeder: FEEDER_ADDR_1.to_string(),
+            delegates: vec![DelegateResponse {
+                delegate: DELEGATE_ADDR.to_string(),
+                expires: Some(2000u64),
+            }]
+        }
+    );
+
+    // the delegate submits for all the symbols of its feeder
+    feed_price(&mut deps, DELEGATE_ADDR, "LUNA", "100", 1500).unwrap();
+    feed_price(&mut deps, DELEGATE_ADDR, "MIR", "3", 1500).unwrap();
+    let msg = QueryMsg::FeedersByAddress {
+        feeder: FEEDER_ADDR_1.to_string(),
+    };
+    let res: FeedersByAddressResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.feeds
+            .into_iter()
+            .map(|feed| feed.submission.unwrap().price)
+            .collect::<Vec<Decimal>>(),
+        vec![
+            Decimal::from_str("100").unwrap(),
+            Decimal::from_str("3").unwrap()
+        ]
+    );
+
+    // another feeder can not take over an active delegate
+    let err = execute(
+        deps.as_mut(),
+        env_at(1500),
+        mock_info(FEEDER_ADDR_2, &[]),
+        authorize_msg(None),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Delegate delegate_0001 is already authorized by another feeder".to_string()
+        }
+    );
+
+    // expired delegates can not submit
+    let err = feed_price(&mut deps, DELEGATE_ADDR, "LUNA", "100", 2000).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env_at(2000),
+        mock_info(FEEDER_ADDR_2, &[]),
+        authorize_msg(None),
+    )
+    .unwrap();
+    let res: DelegatesResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), delegates_msg(FEEDER_ADDR_1)).unwrap())
+            .unwrap();
+    assert_eq!(res.delegates, vec![]);
+
+    // the delegate of a feeder can only submit for the symbols of that feeder
+    let err = feed_price(&mut deps, DELEGATE_ADDR, "MIR", "3", 2100).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    feed_price(&mut deps, DELEGATE_ADDR, "LUNA", "110", 2100).unwrap();
+
+    // revoke
+    let revoke_msg = ExecuteMsg::RevokeDelegate {
+        delegate: DELEGATE_ADDR.to_string(),
+    };
+    let err = execute(deps.as_mut(), mock_env(), info, revoke_msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        owner_info.clone(),
+        revoke_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "revoke_delegate"),
+            attr("feeder", FEEDER_ADDR_2),
+            attr("delegate", DELEGATE_ADDR),
+        ]
+    );
+
+    let err = feed_price(&mut deps, DELEGATE_ADDR, "LUNA", "110", 2200).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(deps.as_mut(), mock_env(), owner_info, revoke_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Delegate delegate_0001 is not authorized".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_commit_reveal() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    register_feed(
+        &mut deps,
+        "LUNA",
+        vec![FEEDER_ADDR_1, FEEDER_ADDR_2, FEEDER_ADDR_3],
+        2,
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::UpdateCommitReveal {
+        symbol: "LUNA".to_string(),
+        commit_reveal: Some(CommitRevealMsg {
+            commit_period: 60u64,
+            reveal_period: 60u64,
+        }),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(FEEDER_ADDR_1, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        owner_info.clone(),
+        ExecuteMsg::UpdateCommitReveal {
+            symbol: "LUNA".to_string(),
+            commit_reveal: Some(CommitRevealMsg {
+                commit_period: 60u64,
+                reveal_period: 0u64,
+            }),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Commit and reveal periods must be greater than zero".to_string()
+        }
+    );
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::UpdateHistorySize {
+        symbol: "LUNA".to_string(),
+        size: 10u32,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    // direct submissions are rejected
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "100", 1000).unwrap();
+    assert_eq!(
+        res.attributes[2],
+        attr("reason", SubmissionError::CommitRevealOnly {}.to_string())
+    );
+    let err = query_price(&deps, "LUNA", 1000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "There is no finalized round for the requested symbol".to_string()
+        }
+    );
+
+    let commit = |deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+                  feeder: &str,
+                  round_id: u64,
+                  price: &str,
+                  salt: &str,
+                  now: u64| {
+        let msg = ExecuteMsg::CommitPrices {
+            commits: vec![(
+                "LUNA".to_string(),
+                Binary::from(commit_hash(
+                    feeder,
+                    "LUNA",
+                    round_id,
+                    Decimal::from_str(price).unwrap(),
+                    salt,
+                )),
+            )],
+        };
+        execute(deps.as_mut(), env_at(now), mock_info(feeder, &[]), msg)
+    };
+    let reveal = |deps: &mut OwnedDeps<MemoryStorage, MockApi, WasmMockQuerier>,
+                  feeder: &str,
+                  price: &str,
+                  salt: &str,
+                  now: u64| {
+        let msg = ExecuteMsg::RevealPrices {
+            reveals: vec![RevealMsg {
+                symbol: "LUNA".to_string(),
+                price: Decimal::from_str(price).unwrap(),
+                salt: salt.to_string(),
+            }],
+        };
+        execute(deps.as_mut(), env_at(now), mock_info(feeder, &[]), msg)
+    };
+
+    // the first commit opens the round
+    let res = commit(&mut deps, FEEDER_ADDR_1, 1, "100", "salt1", 1000).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "commit_prices"),
+            attr("symbol", "LUNA"),
+            attr("round_id", "1"),
+        ]
+    );
+    commit(&mut deps, FEEDER_ADDR_2, 1, "102", "salt2", 1030).unwrap();
+    // a feeder copying the commit of another one can not reveal it
+    let msg = ExecuteMsg::CommitPrices {
+        commits: vec![(
+            "LUNA".to_string(),
+            Binary::from(commit_hash(
+                FEEDER_ADDR_1,
+                "LUNA",
+                1,
+                Decimal::from_str("100").unwrap(),
+                "salt1",
+            )),
+        )],
+    };
+    execute(
+        deps.as_mut(),
+        env_at(1059),
+        mock_info(FEEDER_ADDR_3, &[]),
+        msg,
+    )
+    .unwrap();
+
+    let err = commit(&mut deps, "notfeeder0000", 1, "110", "salt", 1059).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // phases
+    let res = reveal(&mut deps, FEEDER_ADDR_1, "100", "salt1", 1059).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "reveal_prices"),
+            attr("rejected", "LUNA"),
+            attr("reason", "There is no round of LUNA in its reveal phase"),
+        ]
+    );
+    let err = commit(&mut deps, FEEDER_ADDR_3, 1, "110", "salt3", 1060).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Round 1 of LUNA is in its reveal phase".to_string()
+        }
+    );
+
+    reveal(&mut deps, FEEDER_ADDR_1, "100", "salt1", 1060).unwrap();
+    let res = reveal(&mut deps, FEEDER_ADDR_1, "100", "salt1", 1061).unwrap();
+    assert_eq!(
+        res.attributes[1..],
+        [
+            attr("rejected", "LUNA"),
+            attr("reason", "There is no commit to reveal for LUNA"),
+        ]
+    );
+    let res = reveal(&mut deps, FEEDER_ADDR_3, "100", "salt1", 1061).unwrap();
+    assert_eq!(
+        res.attributes[1..],
+        [
+            attr("rejected", "LUNA"),
+            attr(
+                "reason",
+                "Revealed price does not match the commit for LUNA"
+            ),
+        ]
+    );
+
+    // a bad reveal does not abort the rest of the batch
+    let msg = ExecuteMsg::RevealPrices {
+        reveals: vec![
+            RevealMsg {
+                symbol: "LUNA".to_string(),
+                price: Decimal::from_str("101").unwrap(),
+                salt: "salt2".to_string(),
+            },
+            RevealMsg {
+                symbol: "LUNA".to_string(),
+                price: Decimal::from_str("102").unwrap(),
+                salt: "salt2".to_string(),
+            },
+        ],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env_at(1100),
+        mock_info(FEEDER_ADDR_2, &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "reveal_prices"),
+            attr("rejected", "LUNA"),
+            attr(
+                "reason",
+                "Revealed price does not match the commit for LUNA"
+            ),
+            attr("symbol", "LUNA"),
+            attr("price", "102"),
+        ]
+    );
+
+    let msg = QueryMsg::Round {
+        symbol: "LUNA".to_string(),
+    };
+    let res: RoundResponse =
+        from_binary(&query(deps.as_ref(), env_at(1100), msg.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        RoundResponse {
+            symbol: "LUNA".to_string(),
+            commit_period: 60u64,
+            reveal_period: 60u64,
+            current_round: Some(CurrentRoundResponse {
+                round_id: 1u64,
+                started_at: 1000u64,
+                commit_end: 1060u64,
+                reveal_end: 1120u64,
+                committed: vec![
+                    FEEDER_ADDR_1.to_string(),
+                    FEEDER_ADDR_2.to_string(),
+                    FEEDER_ADDR_3.to_string()
+                ],
+                revealed: vec![FEEDER_ADDR_1.to_string(), FEEDER_ADDR_2.to_string()],
+            }),
+            last_round: None,
+        }
+    );
+
+    // the round is served once its reveal phase is over, even before it is finalized
+    assert!(query_price(&deps, "LUNA", 1119).is_err());
+    assert_eq!(
+        query_price(&deps, "LUNA", 1120).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("101").unwrap(),
+            last_updated: 1120u64,
+        }
+    );
+
+    let finalize_msg = ExecuteMsg::FinalizeRound {
+        symbol: "LUNA".to_string(),
+    };
+    let info = mock_info("anyone0000", &[]);
+    let err = execute(
+        deps.as_mut(),
+        env_at(1119),
+        info.clone(),
+        finalize_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "There is no round of LUNA ready to be finalized".to_string()
+        }
+    );
+    let res = execute(deps.as_mut(), env_at(1130), info, finalize_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "finalize_round"),
+            attr("finalized_symbol", "LUNA"),
+            attr("finalized_round", "1"),
+            attr("finalized_price", "101"),
+            attr("non_revealer", FEEDER_ADDR_3),
+        ]
+    );
+
+    let res: RoundResponse =
+        from_binary(&query(deps.as_ref(), env_at(1130), msg.clone()).unwrap()).unwrap();
+    assert_eq!(res.current_round, None);
+    assert_eq!(
+        res.last_round,
+        Some(RoundResultResponse {
+            round_id: 1u64,
+            rate: Some(Decimal::from_str("101").unwrap()),
+            ended_at: 1120u64,
+            revealed: vec![FEEDER_ADDR_1.to_string(), FEEDER_ADDR_2.to_string()],
+            non_revealers: vec![FEEDER_ADDR_3.to_string()],
+        })
+    );
+
+    // the round is recorded in the history at its end
+    let history_msg = QueryMsg::History {
+        symbol: "LUNA".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: HistoryResponse =
+        from_binary(&query(deps.as_ref(), env_at(1130), history_msg).unwrap()).unwrap();
+    assert_eq!(
+        res.observations,
+        vec![ObservationResponse {
+            rate: Decimal::from_str("101").unwrap(),
+            timestamp: 1120u64,
+        }]
+    );
+
+    // reveals are checked against the last finalized price
+    let msg = ExecuteMsg::UpdateGuards {
+        symbol: "LUNA".to_string(),
+        min_update_interval: Some(60u64),
+        max_change_bps: Some(2000u64),
+        min_price: None,
+        max_price: None,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    // a round without enough reveals keeps the last finalized price,
+    // it is closed by the first commit of the next round
+    commit(&mut deps, FEEDER_ADDR_1, 2, "120", "salt1", 1200).unwrap();
+    commit(&mut deps, FEEDER_ADDR_2, 2, "130", "salt2", 1210).unwrap();
+    reveal(&mut deps, FEEDER_ADDR_1, "120", "salt1", 1260).unwrap();
+    let res = reveal(&mut deps, FEEDER_ADDR_2, "130", "salt2", 1260).unwrap();
+    assert_eq!(
+        res.attributes[1..],
+        [
+            attr("rejected", "LUNA"),
+            attr(
+                "reason",
+                SubmissionError::MaxMoveExceeded {
+                    previous: Decimal::from_str("101").unwrap(),
+                    price: Decimal::from_str("130").unwrap(),
+                    max_change_bps: 2000u64,
+                }
+                .to_string()
+            ),
+        ]
+    );
+    let res = commit(&mut deps, FEEDER_ADDR_2, 3, "121", "salt2", 1400).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "commit_prices"),
+            attr("finalized_symbol", "LUNA"),
+            attr("finalized_round", "2"),
+            attr("finalized_price", "none"),
+            attr("non_revealer", FEEDER_ADDR_2),
+            attr("symbol", "LUNA"),
+            attr("round_id", "3"),
+        ]
+    );
+    assert_eq!(
+        query_price(&deps, "LUNA", 1400).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("101").unwrap(),
+            last_updated: 1120u64,
+        }
+    );
+
+    // disabling the mode discards the open round
+    let msg = ExecuteMsg::UpdateCommitReveal {
+        symbol: "LUNA".to_string(),
+        commit_reveal: None,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+    let res = feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "100", 1500).unwrap();
+    assert_eq!(res.attributes[1], attr("symbol", "LUNA"));
+}
+
+#[test]
+fn test_group_feeds() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+    deps.querier.with_group(
+        "group_0001",
+        &[
+            (FEEDER_ADDR_1, 1),
+            (FEEDER_ADDR_2, 1),
+            (FEEDER_ADDR_3, 5),
+            ("feeder_0004", 0),
+        ],
+    );
+
+    let msg = ExecuteMsg::RegisterGroupFeed {
+        symbol: "LUNA".to_string(),
+        group: "group_0001".to_string(),
+        min_submissions: 2,
+        weighted: false,
+    };
+
+    // unauthorized attempt
+    let info = mock_info(FEEDER_ADDR_1, &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the group contract must answer the member queries
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    let invalid_msg = ExecuteMsg::RegisterGroupFeed {
+        symbol: "LUNA".to_string(),
+        group: "group_0002".to_string(),
+        min_submissions: 2,
+        weighted: false,
+    };
+    let err = execute(deps.as_mut(), mock_env(), owner_info.clone(), invalid_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Failed to query the members of group group_0002".to_string()
+        }
+    );
+
+    let invalid_msg = ExecuteMsg::RegisterGroupFeed {
+        symbol: "LUNA".to_string(),
+        group: "group_0001".to_string(),
+        min_submissions: 4,
+        weighted: false,
+    };
+    let err = execute(deps.as_mut(), mock_env(), owner_info.clone(), invalid_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Minimum submissions must be between 1 and the number of members".to_string()
+        }
+    );
+
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let msg = QueryMsg::Feeder {
+        symbol: "LUNA".to_string(),
+    };
+    let res: FeederResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FeederResponse {
+            symbol: "LUNA".to_string(),
+            feeders: vec![],
+            min_submissions: 2,
+            group: Some("group_0001".to_string()),
+            weighted: false,
+            ttl: None,
+        }
+    );
+
+    // only members with a weight can submit
+    let err = feed_price(&mut deps, "feeder_0004", "LUNA", "80", 1000).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = feed_price(&mut deps, "feeder_0005", "LUNA", "80", 1000).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "80", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_2, "LUNA", "81", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_3, "LUNA", "90", 1000).unwrap();
+    let res = query_price(&deps, "LUNA", 1000).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("81").unwrap());
+
+    // the weighted median follows the heaviest member
+    let msg = ExecuteMsg::RegisterGroupFeed {
+        symbol: "LUNA".to_string(),
+        group: "group_0001".to_string(),
+        min_submissions: 2,
+        weighted: true,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+    let res = query_price(&deps, "LUNA", 1000).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("90").unwrap());
+
+    // submissions of addresses that left the group are ignored, equal weights
+    // give the average of the middle prices
+    deps.querier
+        .with_group("group_0001", &[(FEEDER_ADDR_1, 2), (FEEDER_ADDR_2, 2)]);
+    let res = query_price(&deps, "LUNA", 1000).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("80.5").unwrap());
+    let err = feed_price(&mut deps, FEEDER_ADDR_3, "LUNA", "90", 1001).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    deps.querier.with_group("group_0001", &[(FEEDER_ADDR_1, 2)]);
+    let err = query_price(&deps, "LUNA", 1000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Not enough fresh submissions for the requested symbol (1 of 2)".to_string()
+        }
+    );
+
+    // forced prices are set for every member of the group with a weight
+    deps.querier.with_group(
+        "group_0001",
+        &[
+            (FEEDER_ADDR_1, 1),
+            (FEEDER_ADDR_2, 1),
+            (FEEDER_ADDR_3, 5),
+            ("feeder_0004", 0),
+        ],
+    );
+    let msg = ExecuteMsg::ForceUpdatePrices {
+        prices: vec![("LUNA".to_string(), Decimal::from_str("85").unwrap())],
+    };
+    execute(deps.as_mut(), env_at(1100), owner_info, msg).unwrap();
+    let msg = QueryMsg::Submissions {
+        symbol: "LUNA".to_string(),
+    };
+    let res: SubmissionsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.submissions.len(), 3);
+    let res = query_price(&deps, "LUNA", 1100).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("85").unwrap());
+
+    // members with a weight can authorize delegates, group feeds are not listed by address
+    let authorize_msg = ExecuteMsg::AuthorizeDelegate {
+        delegate: "delegate_0001".to_string(),
+        expires: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env_at(1100),
+        mock_info("feeder_0004", &[]),
+        authorize_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env_at(1100),
+        mock_info(FEEDER_ADDR_3, &[]),
+        authorize_msg,
+    )
+    .unwrap();
+    let res = feed_price(&mut deps, "delegate_0001", "LUNA", "95", 1101).unwrap();
+    assert_eq!(res.attributes[1], attr("symbol", "LUNA"));
+    let msg = QueryMsg::FeedersByAddress {
+        feeder: FEEDER_ADDR_3.to_string(),
+    };
+    let res: FeedersByAddressResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.feeds, vec![]);
+
+    // switching back to a feeder list drops the submissions of the other members
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1], 1).unwrap();
+    let msg = QueryMsg::Submissions {
+        symbol: "LUNA".to_string(),
+    };
+    let res: SubmissionsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.submissions.len(), 1);
+    assert_eq!(res.submissions[0].feeder, FEEDER_ADDR_1.to_string());
+
+    // feeds stored before group feeds existed are feeder lists
+    let feed: Feed = from_slice(br#"{"feeders":["feeder_0001"],"min_submissions":1}"#).unwrap();
+    assert_eq!(feed.group, None);
+    assert!(!feed.weighted);
+}
+
+#[test]
+fn test_price_ttl_and_liveness() {
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+    register_feed(&mut deps, "LUNA", vec![FEEDER_ADDR_1, FEEDER_ADDR_2], 1).unwrap();
+    register_feed(&mut deps, "MIR", vec![FEEDER_ADDR_1, FEEDER_ADDR_3], 1).unwrap();
+    register_feed(&mut deps, "ANC", vec![FEEDER_ADDR_3], 1).unwrap();
+
+    let msg = ExecuteMsg::UpdatePriceTtl {
+        symbol: "LUNA".to_string(),
+        ttl: Some(100u64),
+    };
+
+    // unauthorized attempt
+    let info = mock_info(FEEDER_ADDR_1, &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner_info = mock_info(OWNER_ADDR, &[]);
+    let invalid_msg = ExecuteMsg::UpdatePriceTtl {
+        symbol: "LUNA".to_string(),
+        ttl: Some(0u64),
+    };
+    let err = execute(deps.as_mut(), mock_env(), owner_info.clone(), invalid_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Price ttl must be greater than zero".to_string()
+        }
+    );
+
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::UpdatePriceTtl {
+        symbol: "MIR".to_string(),
+        ttl: Some(50u64),
+    };
+    execute(deps.as_mut(), mock_env(), owner_info.clone(), msg).unwrap();
+
+    let msg = QueryMsg::Feeder {
+        symbol: "LUNA".to_string(),
+    };
+    let res: FeederResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.ttl, Some(100u64));
+
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "80", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_2, "LUNA", "82", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_1, "MIR", "10", 1000).unwrap();
+    feed_price(&mut deps, FEEDER_ADDR_3, "ANC", "1", 1000).unwrap();
+
+    let res = query_price(&deps, "LUNA", 1100).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("81").unwrap());
+
+    // all submissions expired
+    let err = query_price(&deps, "LUNA", 1101).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ProxyError {
+            reason: "Not enough fresh submissions for the requested symbol (0 of 1)".to_string()
+        }
+    );
+
+    // expired submissions are left out of the median
+    feed_price(&mut deps, FEEDER_ADDR_1, "LUNA", "90", 1050).unwrap();
+    let res = query_price(&deps, "LUNA", 1101).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("90").unwrap());
+
+    let res: LivenessResponse =
+        from_binary(&query(deps.as_ref(), env_at(1101), QueryMsg::Liveness {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        LivenessResponse {
+            feeders: vec![
+                FeederLivenessResponse {
+                    feeder: FEEDER_ADDR_1.to_string(),
+                    last_submitted: Some(1050u64),
+                    overdue: vec!["MIR".to_string()],
+                },
+                FeederLivenessResponse {
+                    feeder: FEEDER_ADDR_2.to_string(),
+                    last_submitted: Some(1000u64),
+                    overdue: vec!["LUNA".to_string()],
+                },
+                // never submitted on MIR, ANC has no ttl
+                FeederLivenessResponse {
+                    feeder: FEEDER_ADDR_3.to_string(),
+                    last_submitted: Some(1000u64),
+                    overdue: vec!["MIR".to_string()],
+                },
+            ]
+        }
+    );
+
+    // disabling the ttl uses every submission in the window again
+    let msg = ExecuteMsg::UpdatePriceTtl {
+        symbol: "LUNA".to_string(),
+        ttl: None,
+    };
+    execute(deps.as_mut(), mock_env(), owner_info, msg).unwrap();
+    let res = query_price(&deps, "LUNA", 1101).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("86").unwrap());
+}
+
+#[test]
+fn test_migrate() {
+    #[derive(Serialize, Deserialize)]
+    struct BaselineConfig {
+        owner: Addr,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct BaselinePriceInfo {
+        price: Decimal,
+        last_updated_time: u64,
+    }
+
+    let mut deps = mock_dependencies(&[]);
+    init(&mut deps).unwrap();
+
+    // entries stored by the single feeder version
+    let config: Item<BaselineConfig> = Item::new("config");
+    config
+        .save(
+            deps.as_mut().storage,
+            &BaselineConfig {
+                owner: Addr::unchecked(OWNER_ADDR),
+            },
+        )
+        .unwrap();
+    let feeders: Map<&[u8], Addr> = Map::new("feeders");
+    let prices: Map<&[u8], BaselinePriceInfo> = Map::new("prices");
+    feeders
+        .save(
+            deps.as_mut().storage,
+            b"LUNA",
+            &Addr::unchecked(FEEDER_ADDR_1),
+        )
+        .unwrap();
+    feeders
+        .save(
+            deps.as_mut().storage,
+            b"MIR",
+            &Addr::unchecked(FEEDER_ADDR_2),
+        )
+        .unwrap();
+    prices
+        .save(
+            deps.as_mut().storage,
+            b"LUNA",
+            &BaselinePriceInfo {
+                price: Decimal::from_str("100").unwrap(),
+                last_updated_time: 1000u64,
+            },
+        )
+        .unwrap();
+    assert!(query_price(&deps, "LUNA", 1000).is_err());
+
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(res.attributes, vec![attr("action", "migrate")]);
+
+    assert_eq!(
+        query_price(&deps, "LUNA", 1000).unwrap(),
+        ProxyPriceResponse {
+            rate: Decimal::from_str("100").unwrap(),
+            last_updated: 1000u64,
+        }
+    );
+    let msg = QueryMsg::Feeder {
+        symbol: "MIR".to_string(),
+    };
+    let res: FeederResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        FeederResponse {
+            symbol: "MIR".to_string(),
+            feeders: vec![FEEDER_ADDR_2.to_string()],
+            min_submissions: 1,
+            group: None,
+            weighted: false,
+            ttl: None,
+        }
+    );
+    let msg = QueryMsg::FeedersByAddress {
+        feeder: FEEDER_ADDR_1.to_string(),
+    };
+    let res: FeedersByAddressResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.feeds.len(), 1);
+    assert_eq!(res.feeds[0].symbol, "LUNA".to_string());
+
+    // the legacy entries are moved
+    assert_eq!(
+        LEGACY_FEEDERS.may_load(&deps.storage, b"LUNA").unwrap(),
+        None
+    );
+    assert_eq!(
+        LEGACY_PRICES.may_load(&deps.storage, b"LUNA").unwrap(),
+        None
+    );
+
+    // the stored config has no submission window nor maximum observation age
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config_res: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config_res,
+        ConfigResponse {
+            owner: OWNER_ADDR.to_string(),
+            submission_window: None,
+            max_observation_age: None,
+        }
+    );
+
+    feed_price(&mut deps, FEEDER_ADDR_2, "MIR", "5", 1100).unwrap();
+    let res = query_price(&deps, "MIR", 1100).unwrap();
+    assert_eq!(res.rate, Decimal::from_str("5").unwrap());
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::empty_line_after_doc_comments)]
+
 use cosmwasm_std::{Addr, StdError, StdResult};
 use std::array::TryFromSliceError;
 use std::convert::TryInto;
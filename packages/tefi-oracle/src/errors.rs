@@ -45,6 +45,24 @@ pub enum ContractError {
     #[error("There is no price available with the requested constrains")]
     PriceNotAvailable {},
 
+    #[error("There is no cached price for this symbol")]
+    CachedPriceNotFound {},
+
+    #[error("History size can not exceed the maximum ({max})")]
+    InvalidHistorySize { max: u32 },
+
+    #[error("The price history does not cover the requested period")]
+    HistoryNotAvailable {},
+
+    #[error("EMA half-life must be greater than zero")]
+    InvalidEmaHalfLife {},
+
+    #[error("There is no EMA price available for this symbol")]
+    EmaNotAvailable {},
+
+    #[error("There is no jump limit configured for this symbol")]
+    JumpLimitNotFound {},
+
     #[error("Proxy error: {reason}")]
     ProxyError { reason: String },
 }
@@ -0,0 +1,211 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{Decimal, StdResult, Storage};
+use cw_storage_plus::{Map, U32Key};
+
+use crate::errors::ContractError;
+
+// ring buffer state of the price history for each symbol
+pub const HISTORY_STATE: Map<&[u8], HistoryState> = Map::new("history_state");
+// price history observations for each symbol, indexed by ring buffer slot
+pub const OBSERVATIONS: Map<(&[u8], U32Key), Observation> = Map::new("observations");
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HistoryState {
+    pub size: u32,
+    // total number of observations recorded, used as write cursor
+    pub count: u64,
+}
+
+impl HistoryState {
+    /// Returns the ring buffer slot of the observation with sequence number `seq`
+    fn slot(&self, seq: u64) -> U32Key {
+        U32Key::new((seq % self.size as u64) as u32)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Observation {
+    pub rate: Decimal,
+    pub timestamp: u64,
+}
+
+/// Appends an observation to the price history of `symbol`, overwriting the oldest
+/// one if the buffer is full. Does nothing if the history is not enabled
+pub fn push_observation(
+    storage: &mut dyn Storage,
+    symbol: &str,
+    observation: &Observation,
+) -> StdResult<()> {
+    let mut state: HistoryState = match HISTORY_STATE.may_load(storage, symbol.as_bytes())? {
+        Some(state) => state,
+        None => return Ok(()),
+    };
+
+    // a second observation within the same block replaces the previous one
+    if state.count > 0 {
+        let last: Observation =
+            OBSERVATIONS.load(storage, (symbol.as_bytes(), state.slot(state.count - 1)))?;
+        if last.timestamp == observation.timestamp {
+            let last_slot = state.slot(state.count - 1);
+            return OBSERVATIONS.save(storage, (symbol.as_bytes(), last_slot), observation);
+        }
+    }
+
+    OBSERVATIONS.save(
+        storage,
+        (symbol.as_bytes(), state.slot(state.count)),
+        observation,
+    )?;
+    state.count += 1;
+
+    HISTORY_STATE.save(storage, symbol.as_bytes(), &state)
+}
+
+/// Returns the price history of `symbol` sorted from oldest to newest
+pub fn load_observations(storage: &dyn Storage, symbol: &str) -> StdResult<Vec<Observation>> {
+    let state: HistoryState = match HISTORY_STATE.may_load(storage, symbol.as_bytes())? {
+        Some(state) => state,
+        None => return Ok(vec![]),
+    };
+
+    let len = state.count.min(state.size as u64);
+    (state.count - len..state.count)
+        .map(|seq| OBSERVATIONS.load(storage, (symbol.as_bytes(), state.slot(seq))))
+        .collect()
+}
+
+/// Changes the price history size of `symbol`, keeping the most recent observations
+/// that fit in the new buffer. A `size` of 0 disables and clears the history
+pub fn resize_history(storage: &mut dyn Storage, symbol: &str, size: u32) -> StdResult<()> {
+    let observations: Vec<Observation> = load_observations(storage, symbol)?;
+
+    if let Some(state) = HISTORY_STATE.may_load(storage, symbol.as_bytes())? {
+        let used_slots = state.count.min(state.size as u64) as u32;
+        for slot in 0..used_slots {
+            OBSERVATIONS.remove(storage, (symbol.as_bytes(), U32Key::new(slot)));
+        }
+    }
+
+    if size == 0 {
+        HISTORY_STATE.remove(storage, symbol.as_bytes());
+        return Ok(());
+    }
+
+    let mut state = HistoryState { size, count: 0 };
+    let skip = observations.len().saturating_sub(size as usize);
+    for observation in observations.iter().skip(skip) {
+        OBSERVATIONS.save(
+            storage,
+            (symbol.as_bytes(), state.slot(state.count)),
+            observation,
+        )?;
+        state.count += 1;
+    }
+
+    HISTORY_STATE.save(storage, symbol.as_bytes(), &state)
+}
+
+/// Returns the price at `timestamp` from a history sorted from oldest to newest.
+/// Between two observations the price is linearly interpolated, after the latest
+/// observation the latest price is returned as long as it is at most `max_gap` seconds old
+pub fn price_at(
+    observations: &[Observation],
+    timestamp: u64,
+    max_gap: Option<u64>,
+) -> Result<Decimal256, ContractError> {
+    let position = observations
+        .iter()
+        .rposition(|item| item.timestamp <= timestamp)
+        .ok_or(ContractError::HistoryNotAvailable {})?;
+    check_gap(observations, position, timestamp, max_gap)?;
+
+    match observations.get(position + 1) {
+        Some(next) => Ok(interpolate(&observations[position], next, timestamp)),
+        None => Ok(observations[position].rate.into()),
+    }
+}
+
+/// Returns the time weighted average price between `start` and `end`, using
+/// the same price curve as [`price_at`]. The history must begin at or before `start`
+/// and the latest observation can be extended to `end` by at most `max_gap` seconds
+pub fn time_weighted_average(
+    observations: &[Observation],
+    start: u64,
+    end: u64,
+    max_gap: Option<u64>,
+) -> Result<Decimal256, ContractError> {
+    match observations.first() {
+        Some(oldest) if oldest.timestamp <= start => {}
+        _ => return Err(ContractError::HistoryNotAvailable {}),
+    }
+
+    if start >= end {
+        return price_at(observations, end, max_gap);
+    }
+
+    let position = observations
+        .iter()
+        .rposition(|item| item.timestamp <= end)
+        .ok_or(ContractError::HistoryNotAvailable {})?;
+    check_gap(observations, position, end, max_gap)?;
+
+    let mut cumulative = Decimal256::zero();
+    for (i, current) in observations.iter().enumerate() {
+        // the latest observation holds its price until `end`
+        let next: Option<&Observation> = observations.get(i + 1);
+        let segment_end = next.map(|item| item.timestamp).unwrap_or(end);
+
+        let from = current.timestamp.max(start);
+        let to = segment_end.min(end);
+        if from >= to {
+            continue;
+        }
+
+        let (from_price, to_price) = match next {
+            Some(next) => (
+                interpolate(current, next, from),
+                interpolate(current, next, to),
+            ),
+            None => (current.rate.into(), current.rate.into()),
+        };
+        cumulative += (from_price + to_price) * Decimal256::from_ratio(to - from, 2u64);
+    }
+
+    Ok(cumulative / Decimal256::from_uint256(Uint256::from(end - start)))
+}
+
+/// Fails if the observation at `position` is the latest one and is
+/// more than `max_gap` seconds older than `timestamp`
+fn check_gap(
+    observations: &[Observation],
+    position: usize,
+    timestamp: u64,
+    max_gap: Option<u64>,
+) -> Result<(), ContractError> {
+    if let (Some(max_gap), true) = (max_gap, position + 1 == observations.len()) {
+        if observations[position].timestamp.saturating_add(max_gap) < timestamp {
+            return Err(ContractError::HistoryNotAvailable {});
+        }
+    }
+
+    Ok(())
+}
+
+/// Linearly interpolates the price at `timestamp` between observations `a` and `b`
+fn interpolate(a: &Observation, b: &Observation, timestamp: u64) -> Decimal256 {
+    let a_rate: Decimal256 = a.rate.into();
+    let b_rate: Decimal256 = b.rate.into();
+    if b.timestamp <= a.timestamp {
+        return b_rate;
+    }
+
+    let ratio = Decimal256::from_ratio(timestamp - a.timestamp, b.timestamp - a.timestamp);
+    if b_rate >= a_rate {
+        a_rate + (b_rate - a_rate) * ratio
+    } else {
+        a_rate - (a_rate - b_rate) * ratio
+    }
+}
@@ -5,12 +5,16 @@ use cosmwasm_std::Decimal;
 
 pub const DEFAULT_PRIORITY: u8 = 10;
 pub const MAX_WHITELISTED_PROXIES: u8 = 15;
+pub const MAX_HISTORY_SIZE: u32 = 200;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: String,
     pub base_denom: String,
     pub max_proxies_per_symbol: u8,
+    pub cache_max_age: Option<u64>,
+    pub stale_threshold: Option<u64>,
+    pub demote_after_failures: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -20,6 +24,17 @@ pub enum HubExecuteMsg {
     UpdateOwner { owner: String },
     /// Owner operation to update the max_proxies_per_symbol parameter
     UpdateMaxProxies { max_proxies_per_symbol: u8 },
+    /// Owner operation to update the cache_max_age parameter.
+    /// If set to `None`, price queries will never fall back to the cache
+    UpdateCacheMaxAge { cache_max_age: Option<u64> },
+    /// Owner operation to update the source health parameters.
+    /// `stale_threshold` is the max price age in seconds for a probe to succeed.
+    /// If `demote_after_failures` is set, sources that fail that many probes in a row
+    /// are queried after the healthy ones regardless of their priority
+    UpdateHealthConfig {
+        stale_threshold: Option<u64>,
+        demote_after_failures: Option<u32>,
+    },
     /// Register a new source for a symbol
     RegisterSource {
         symbol: String,
@@ -50,6 +65,31 @@ pub enum HubExecuteMsg {
     InsertAssetSymbolMap {
         map: Vec<(String, String)>, // (address, symbol)
     },
+    /// Permissionless operation to fetch the current price of the provided
+    /// symbols from their sources and store it in the price cache.
+    /// If price history is enabled for a symbol, the price is also recorded as an observation
+    RefreshPrices { symbols: Vec<String> },
+    /// Owner operation to set the number of observations kept in the price history
+    /// of a symbol. Setting it to 0 disables and clears the history
+    UpdateHistorySize { symbol: String, size: u32 },
+    /// Owner operation to set the half-life in seconds of the exponential moving
+    /// average price of a symbol. If set to `None`, the EMA is disabled
+    UpdateEmaHalfLife {
+        symbol: String,
+        half_life: Option<u64>,
+    },
+    /// Owner operation to limit how fast the refreshed price of a symbol can move,
+    /// relative to the last cached price. Observations outside the allowed band are
+    /// rejected, or clamped to the band if `clamp` is true.
+    /// If `max_change_bps_per_second` is `None`, the limit is removed
+    UpdateJumpLimit {
+        symbol: String,
+        max_change_bps_per_second: Option<u64>,
+        clamp: bool,
+    },
+    /// Permissionless operation to query every source of the provided symbols
+    /// and record the result in their health counters
+    ProbeSources { symbols: Vec<String> },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -70,15 +110,21 @@ pub enum HubQueryMsg {
     SourcesBySymbol { symbol: String },
     /// Queries the highes priority available price within the timeframe
     /// If timeframe is not provided, it will ignore the price age
+    /// If cache_fallback is true and no source is available, the cached price
+    /// is returned as long as it is not older than the configured cache_max_age
     Price {
         asset_token: String,
         timeframe: Option<u64>,
+        cache_fallback: Option<bool>,
     },
     /// Queries the highes priority available price within the timeframe
     /// If timeframe is not provided, it will ignore the price age
+    /// If cache_fallback is true and no source is available, the cached price
+    /// is returned as long as it is not older than the configured cache_max_age
     PriceBySymbol {
         symbol: String,
         timeframe: Option<u64>,
+        cache_fallback: Option<bool>,
     },
     /// Queries all registered proxy prices for the provied asset_token
     PriceList { asset_token: String },
@@ -94,6 +140,30 @@ pub enum HubQueryMsg {
     /// way of checking if a price feed is valid and available before registering
     /// Returns the PriceResponse or an error
     CheckSource { proxy_addr: String, symbol: String },
+    /// Queries the last price stored by `RefreshPrices` for the provided symbol
+    CachedPrice { symbol: String },
+    /// Queries the time weighted average price of the last `window` seconds,
+    /// computed from the recorded price history.
+    /// If timeframe is provided, the latest observation must be at most that old
+    Twap {
+        symbol: String,
+        window: u64,
+        timeframe: Option<u64>,
+    },
+    /// Queries the price at `timestamp`, interpolated between the two closest
+    /// observations of the recorded price history.
+    /// If timeframe is provided, the latest observation is not used past that many seconds
+    PriceAt {
+        symbol: String,
+        timestamp: u64,
+        timeframe: Option<u64>,
+    },
+    /// Queries the exponential moving average price, updated on every `RefreshPrices`
+    EmaPrice { symbol: String },
+    /// Queries the jump limit of a symbol and the last time an observation was rejected
+    JumpLimit { symbol: String },
+    /// Queries the health counters of all registered sources of a symbol
+    SourceHealth { symbol: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -101,6 +171,9 @@ pub struct ConfigResponse {
     pub owner: String,
     pub base_denom: String,
     pub max_proxies_per_symbol: u8,
+    pub cache_max_age: Option<u64>,
+    pub stale_threshold: Option<u64>,
+    pub demote_after_failures: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -109,6 +182,56 @@ pub struct PriceResponse {
     pub last_updated: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CachedPriceResponse {
+    pub symbol: String,
+    pub rate: Decimal,
+    pub last_updated: u64, // timestamp reported by the source
+    pub cached_at: u64,    // block time when the price was stored
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TwapResponse {
+    pub symbol: String,
+    pub rate: Decimal,
+    pub window: u64,
+    pub last_updated: u64, // timestamp of the latest observation
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmaPriceResponse {
+    pub symbol: String,
+    pub rate: Decimal,
+    pub half_life: u64,
+    pub last_updated: u64, // block time of the last EMA update
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JumpLimitResponse {
+    pub symbol: String,
+    pub max_change_bps_per_second: u64,
+    pub clamp: bool,
+    pub last_rejected: Option<u64>, // block time of the last rejected or clamped observation
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SourceHealthResponse {
+    pub symbol: String,
+    pub sources: Vec<ProxyHealthResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProxyHealthResponse {
+    pub priority: u8,
+    pub proxy: ProxyInfoResponse,
+    pub successes: u64,
+    pub failures: u64,
+    pub stale: u64,
+    pub consecutive_failures: u32, // failed or stale probes in a row
+    pub last_probed: u64,
+    pub demoted: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PriceQueryResult {
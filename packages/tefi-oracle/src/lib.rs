@@ -2,6 +2,8 @@
 pub mod de;
 #[cfg(feature = "internal")]
 pub mod errors;
+#[cfg(feature = "internal")]
+pub mod history;
 pub mod hub;
 pub mod proxy;
 pub mod querier;
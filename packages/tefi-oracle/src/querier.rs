@@ -38,6 +38,7 @@ pub fn query_asset_price(
         msg: to_binary(&HubQueryMsg::Price {
             asset_token: String::from(asset_token),
             timeframe,
+            cache_fallback: None,
         })?,
     }))?;
 